@@ -1,41 +1,310 @@
-use chrono::{Datelike, NaiveDate};
-use clap::Parser;
+use chrono::{Datelike, Local, NaiveDate, TimeDelta};
+use clap::{ColorChoice, Parser, Subcommand, ValueEnum};
+use rusty_tasks::todo::TaskState;
+use std::str::FromStr;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
 pub struct Args {
     /// set config file to use
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", global = true)]
     pub config: Option<String>,
-    /// show current config file
-    #[arg(short = 'C', long)]
-    pub current_config: bool,
 
-    /// view a specific date's file (YYYY-MM-DD)
-    #[arg(short, long)]
-    pub date: Option<String>,
-    /// view previous day's notes
+    /// increase logging level
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// open a notes file in the editor (default when no command is given)
+    Open(DateArgs),
+    /// list notes files
+    List(ListArgs),
+    /// show the config file in use
+    Config(ConfigArgs),
+    /// create today's file from the most recent notes without opening it
+    Carry(CarryArgs),
+    /// add a task to today's file
+    Add(AddArgs),
+    /// mark a task in today's file as done
+    Done(SelectArgs),
+    /// toggle a task in today's file between done and not done
+    Toggle(SelectArgs),
+    /// print the tasks of a notes file
+    Show(ShowArgs),
+    /// search the tasks of all notes files
+    Search(SearchArgs),
+    /// show every day a task appeared on and its status
+    History(HistoryArgs),
+    /// print statistics about completed and carried over tasks
+    Stats(StatsArgs),
+    /// write a review of a week or month of notes and open it in the editor
+    Review(ReviewArgs),
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command::Open(DateArgs::default())
+    }
+}
+
+#[derive(clap::Args, Debug, Default)]
+pub struct DateArgs {
+    /// use a specific date's file (YYYY-MM-DD)
+    #[arg(short, long, value_parser = parse_date, conflicts_with = "previous")]
+    pub date: Option<NaiveDate>,
+    /// use the file from n days ago
     #[arg(short = 'p', long, default_value_t = 0)]
     pub previous: u16,
-    /// list closest files to date
-    #[arg(short, long)]
-    pub list: bool,
+}
+
+impl DateArgs {
+    /// date selected by the arguments, relative to `today`
+    pub fn target(&self, today: &NaiveDate) -> NaiveDate {
+        self.date
+            .unwrap_or(*today - TimeDelta::try_days(self.previous.into()).unwrap())
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub date: DateArgs,
     /// number of files to list
     #[arg(short, long, default_value_t = 5)]
     pub number: usize,
-    /// list closest files to date
-    #[arg(short = 'L', long)]
-    pub list_all: bool,
+    /// list all notes files
+    #[arg(short, long, conflicts_with_all = ["number", "date", "previous"])]
+    pub all: bool,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// print the number of tasks with each tag and context across all notes
+    /// instead of listing files
+    #[arg(long = "count-tags", conflicts_with_all = ["number", "date", "previous", "all"])]
+    pub count_tags: bool,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
 
-    /// increase logging level
-    #[arg(short, long, action = clap::ArgAction::Count)]
-    pub verbose: u8,
+#[derive(clap::Args, Debug, Default)]
+pub struct TagArgs {
+    /// only tasks with TAG, can be repeated
+    #[arg(short, long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// only tasks with CONTEXT, can be repeated
+    #[arg(long = "context", value_name = "CONTEXT")]
+    pub contexts: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CarryArgs {
+    /// print the file that would be created (or a diff against the existing
+    /// file) instead of writing it
+    #[arg(short = 'n', long)]
+    pub dry_run: bool,
+    /// merge unfinished tasks from every file since the last merge instead
+    /// of only the most recent one
+    #[arg(short, long)]
+    pub merge: bool,
+    /// merge unfinished tasks from every file since this date
+    #[arg(short, long, value_parser = parse_date)]
+    pub since: Option<NaiveDate>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct AddArgs {
+    /// section to add the task to [default: first configured section]
+    #[arg(short, long)]
+    pub section: Option<String>,
+    /// text of the task
+    #[arg(required = true)]
+    pub text: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SelectArgs {
+    /// section containing the task [default with --match: all configured sections]
+    #[arg(required_unless_present = "pattern")]
+    pub section: Option<String>,
+    /// 1 based position of the task, use dots for subtasks (e.g. 3.1)
+    #[arg(required_unless_present = "pattern")]
+    pub index: Option<TaskPath>,
+    /// select the only task containing TEXT (ignoring case)
+    #[arg(
+        short = 'm',
+        long = "match",
+        value_name = "TEXT",
+        conflicts_with = "index"
+    )]
+    pub pattern: Option<String>,
+}
+
+/// Dotted path to a task, e.g. `3.1` is the first subtask of the third task
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskPath(pub Vec<usize>);
+
+impl FromStr for TaskPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split('.')
+            .map(|part| match part.parse() {
+                Ok(0) | Err(_) => Err(format!("invalid task index: {}", s)),
+                Ok(i) => Ok(i),
+            })
+            .collect::<Result<_, _>>()
+            .map(TaskPath)
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ShowArgs {
+    #[command(flatten)]
+    pub date: DateArgs,
+    /// section to show, can be repeated [default: all configured sections]
+    #[arg(short, long)]
+    pub section: Vec<String>,
+    /// hide completed tasks
+    #[arg(long)]
+    pub pending: bool,
+    /// color tasks by their status
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+    /// highlight unfinished tasks first seen more than DAYS ago
+    /// [default: stale_after_days from the config]
+    #[arg(long, value_name = "DAYS")]
+    pub stale_after: Option<u32>,
+    /// only show tasks due on or before DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub due_by: Option<NaiveDate>,
+    /// only show tasks scheduled on or before DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub scheduled_by: Option<NaiveDate>,
+    /// hide tasks with a start date after today
+    #[arg(long)]
+    pub available: bool,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SearchArgs {
+    /// only tasks containing TEXT (ignoring case)
+    #[arg(value_name = "TEXT")]
+    pub query: Option<String>,
+    /// only files on or after DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub since: Option<NaiveDate>,
+    /// only files on or before DATE
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    pub until: Option<NaiveDate>,
+    /// only tasks in STATE (open, in-progress, deferred, done or cancelled),
+    /// can be repeated
+    #[arg(long = "status", value_name = "STATE")]
+    pub states: Vec<TaskState>,
+    /// section to search, can be repeated [default: all configured sections]
+    #[arg(short, long)]
+    pub section: Vec<String>,
+    #[command(flatten)]
+    pub tags: TagArgs,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct HistoryArgs {
+    /// follow the tasks containing TEXT (ignoring case)
+    #[arg(value_name = "TEXT")]
+    pub query: String,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// only files on or after DATE, or from the last N days or weeks
+    /// (e.g. 30d or 4w) [default: all files]
+    #[arg(long, value_name = "DATE", value_parser = parse_since)]
+    pub since: Option<NaiveDate>,
+    /// draw charts of the tasks completed per day and the open tasks of each
+    /// section over time instead of the table of days
+    #[arg(long, conflicts_with = "format")]
+    pub chart: bool,
+    /// draw charts with ASCII characters only
+    #[arg(long, requires = "chart")]
+    pub ascii: bool,
+    /// output format
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ReviewArgs {
+    /// review a week, from Monday to Sunday (default)
+    #[arg(short, long, conflicts_with = "month")]
+    pub week: bool,
+    /// review a month
+    #[arg(short, long)]
+    pub month: bool,
+    /// review the week or month containing DATE [default: today]
+    #[arg(short, long, value_name = "DATE", value_parser = parse_date)]
+    pub date: Option<NaiveDate>,
+    /// write the review again even if its file already exists
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// human readable text
+    Text,
+    /// JSON, see the README for the schema
+    Json,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ConfigArgs {
+    /// print the loaded config instead of its location
+    #[arg(short, long)]
+    pub show: bool,
+}
+
+fn parse_date(date_str: &str) -> Result<NaiveDate, String> {
+    smart_parse_date(date_str, &Local::now().date_naive())
+        .ok_or(format!("could not parse date: {}", date_str))
+}
+
+fn parse_since(since: &str) -> Result<NaiveDate, String> {
+    relative_date(since, &Local::now().date_naive())
+        .or_else(|| smart_parse_date(since, &Local::now().date_naive()))
+        .ok_or(format!("could not parse date: {}", since))
+}
+
+/// Date `n` days (`nd`) or weeks (`nw`) before `cur_date`
+pub fn relative_date(since: &str, cur_date: &NaiveDate) -> Option<NaiveDate> {
+    let (number, days) = match since.char_indices().last()? {
+        (i, 'd') => (&since[..i], 1),
+        (i, 'w') => (&since[..i], 7),
+        _ => return None,
+    };
+    let number: i64 = number.parse().ok()?;
+    cur_date.checked_sub_signed(TimeDelta::try_days(number.checked_mul(days)?)?)
 }
 
 pub fn smart_parse_date(date_str: &str, cur_date: &NaiveDate) -> Option<NaiveDate> {
     let full_date_fmt = "%Y-%m-%d";
 
-    if let Ok(date) = NaiveDate::parse_from_str(date_str, &full_date_fmt) {
+    if let Ok(date) = NaiveDate::parse_from_str(date_str, full_date_fmt) {
         return Some(date);
     }
     let parts: Vec<&str> = date_str.split('-').collect();
@@ -57,10 +326,213 @@ pub fn smart_parse_date(date_str: &str, cur_date: &NaiveDate) -> Option<NaiveDat
 #[cfg(test)]
 mod test {
     use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args() {
+        Args::command().debug_assert();
+
+        let args = Args::try_parse_from(["rusty-tasks"]).unwrap();
+        assert!(args.command.is_none());
+        assert!(Args::try_parse_from(["rusty-tasks", "list", "--format", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_open_args() {
+        let args = Args::try_parse_from(["rusty-tasks", "open", "-d", "2024-01-03"]).unwrap();
+        let Some(Command::Open(date_args)) = args.command else {
+            panic!("expected open command");
+        };
+        assert_eq!(date_args.date, NaiveDate::from_ymd_opt(2024, 1, 3));
+
+        assert!(Args::try_parse_from(["rusty-tasks", "open", "-d", "2024-13-01"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "open", "-d", "3", "-p", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_list_args() {
+        let today = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        let args = Args::try_parse_from(["rusty-tasks", "list", "-p", "2", "-v"]).unwrap();
+        assert_eq!(args.verbose, 1);
+        let Some(Command::List(list_args)) = args.command else {
+            panic!("expected list command");
+        };
+        assert_eq!(
+            list_args.date.target(&today),
+            NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+        );
+
+        assert!(Args::try_parse_from(["rusty-tasks", "list", "-a", "-n", "3"]).is_err());
+        assert!(
+            Args::try_parse_from(["rusty-tasks", "list", "--count-tags", "-t", "work"]).is_ok()
+        );
+        assert!(Args::try_parse_from(["rusty-tasks", "list", "--count-tags", "-a"]).is_err());
+    }
+
+    #[test]
+    fn test_add_args() {
+        let args =
+            Args::try_parse_from(["rusty-tasks", "add", "-s", "Weekly", "review", "PR"]).unwrap();
+        let Some(Command::Add(add_args)) = args.command else {
+            panic!("expected add command");
+        };
+        assert_eq!(add_args.section, Some("Weekly".into()));
+        assert_eq!(add_args.text.join(" "), "review PR");
+        assert!(Args::try_parse_from(["rusty-tasks", "add", "-s", "Weekly"]).is_err());
+    }
+
+    #[test]
+    fn test_select_args() {
+        let args = Args::try_parse_from(["rusty-tasks", "done", "Daily", "3.1"]).unwrap();
+        let Some(Command::Done(select_args)) = args.command else {
+            panic!("expected done command");
+        };
+        assert_eq!(select_args.section, Some("Daily".into()));
+        assert_eq!(select_args.index, Some(TaskPath(vec![3, 1])));
+        assert!(Args::try_parse_from(["rusty-tasks", "done", "--match", "deploy"]).is_ok());
+        assert!(Args::try_parse_from(["rusty-tasks", "toggle", "Daily", "-m", "deploy"]).is_ok());
+        assert!(Args::try_parse_from(["rusty-tasks", "done", "Daily"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "done", "Daily", "1", "-m", "x"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "done", "Daily", "1.0"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "done", "Daily", "a"]).is_err());
+    }
+
+    #[test]
+    fn test_show_args() {
+        let args = Args::try_parse_from([
+            "rusty-tasks",
+            "show",
+            "-s",
+            "Daily",
+            "-s",
+            "Weekly",
+            "--pending",
+            "--color",
+            "never",
+            "--stale-after",
+            "3",
+            "--due-by",
+            "2024-01-05",
+            "--available",
+            "-t",
+            "work",
+            "--tag",
+            "urgent",
+            "--context",
+            "office",
+            "-f",
+            "json",
+        ])
+        .unwrap();
+        let Some(Command::Show(show_args)) = args.command else {
+            panic!("expected show command");
+        };
+        assert_eq!(show_args.section, vec!["Daily", "Weekly"]);
+        assert!(show_args.pending);
+        assert_eq!(show_args.color, ColorChoice::Never);
+        assert_eq!(show_args.stale_after, Some(3));
+        assert_eq!(show_args.due_by, NaiveDate::from_ymd_opt(2024, 1, 5));
+        assert_eq!(show_args.scheduled_by, None);
+        assert!(show_args.available);
+        assert_eq!(show_args.tags.tags, vec!["work", "urgent"]);
+        assert_eq!(show_args.tags.contexts, vec!["office"]);
+        assert_eq!(show_args.format, Format::Json);
+    }
+
+    #[test]
+    fn test_search_args() {
+        let args = Args::try_parse_from([
+            "rusty-tasks",
+            "search",
+            "deploy",
+            "--since",
+            "2026-09-01",
+            "--status",
+            "open",
+            "--status",
+            "in-progress",
+            "-s",
+            "Weekly",
+        ])
+        .unwrap();
+        let Some(Command::Search(search_args)) = args.command else {
+            panic!("expected search command");
+        };
+        assert_eq!(search_args.query.as_deref(), Some("deploy"));
+        assert_eq!(search_args.since, NaiveDate::from_ymd_opt(2026, 9, 1));
+        assert_eq!(
+            search_args.states,
+            vec![TaskState::Open, TaskState::InProgress]
+        );
+        assert_eq!(search_args.section, vec!["Weekly"]);
+        assert!(Args::try_parse_from(["rusty-tasks", "search", "--status", "finished"]).is_err());
+    }
+
+    #[test]
+    fn test_history_args() {
+        let args = Args::try_parse_from(["rusty-tasks", "history", "write RFC"]).unwrap();
+        let Some(Command::History(history_args)) = args.command else {
+            panic!("expected history command");
+        };
+        assert_eq!(history_args.query, "write RFC");
+        assert!(Args::try_parse_from(["rusty-tasks", "history"]).is_err());
+    }
+
+    #[test]
+    fn test_stats_args() {
+        let args = Args::try_parse_from(["rusty-tasks", "stats", "--since", "2026-09-01"]).unwrap();
+        let Some(Command::Stats(stats_args)) = args.command else {
+            panic!("expected stats command");
+        };
+        assert_eq!(stats_args.since, NaiveDate::from_ymd_opt(2026, 9, 1));
+        assert!(!stats_args.chart);
+        assert!(Args::try_parse_from(["rusty-tasks", "stats", "--since", "30d"]).is_ok());
+        assert!(Args::try_parse_from(["rusty-tasks", "stats", "--since", "2024-13-01"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "stats", "--chart", "--ascii"]).is_ok());
+        assert!(Args::try_parse_from(["rusty-tasks", "stats", "--ascii"]).is_err());
+        assert!(Args::try_parse_from(["rusty-tasks", "stats", "--chart", "-f", "json"]).is_err());
+
+        let today = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        assert_eq!(
+            relative_date("30d", &today),
+            NaiveDate::from_ymd_opt(2023, 12, 4)
+        );
+        assert_eq!(
+            relative_date("2w", &today),
+            NaiveDate::from_ymd_opt(2023, 12, 20)
+        );
+        assert_eq!(relative_date("d", &today), None);
+        assert_eq!(relative_date("3", &today), None);
+    }
+
+    #[test]
+    fn test_review_args() {
+        let args =
+            Args::try_parse_from(["rusty-tasks", "review", "--month", "-d", "2026-09-03"]).unwrap();
+        let Some(Command::Review(review_args)) = args.command else {
+            panic!("expected review command");
+        };
+        assert!(review_args.month);
+        assert!(!review_args.week);
+        assert_eq!(review_args.date, NaiveDate::from_ymd_opt(2026, 9, 3));
+        assert!(Args::try_parse_from(["rusty-tasks", "review", "--week", "--month"]).is_err());
+    }
+
+    #[test]
+    fn test_carry_args() {
+        let args =
+            Args::try_parse_from(["rusty-tasks", "carry", "-n", "--since", "2024-01-03"]).unwrap();
+        let Some(Command::Carry(carry_args)) = args.command else {
+            panic!("expected carry command");
+        };
+        assert!(carry_args.dry_run);
+        assert!(!carry_args.merge);
+        assert_eq!(carry_args.since, NaiveDate::from_ymd_opt(2024, 1, 3));
+    }
 
     #[test]
     fn test_smart_parse_date() {
-        let good_date = NaiveDate::from_ymd_opt(2024, 01, 03).expect("Invalid date specified");
+        let good_date = NaiveDate::from_ymd_opt(2024, 1, 3).expect("Invalid date specified");
         let good_date_str = good_date.format("%Y-%m-%d").to_string();
 
         assert_eq!(
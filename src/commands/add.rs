@@ -0,0 +1,27 @@
+use super::{edit_file, Context};
+use crate::cli::AddArgs;
+use rusty_tasks::file;
+use rusty_tasks::todo::{created_marker, TaskDates};
+use rusty_tasks::{Error, Result};
+
+/// add a task to a section of today's file
+pub fn add(ctx: &Context, args: AddArgs) -> Result<()> {
+    let section = match args.section {
+        Some(section) => section,
+        None => ctx
+            .cfg
+            .sections
+            .first()
+            .cloned()
+            .ok_or_else(|| Error::SectionNotFound("(no sections configured)".into()))?,
+    };
+    let mut text = args.text.join(" ");
+    if ctx.cfg.track_age && TaskDates::parse(&text).created.is_none() {
+        text.push_str(&created_marker(&ctx.today));
+    }
+
+    let todo_file = ctx.resolve_file(&ctx.today)?;
+    edit_file(&todo_file, |contents, root| {
+        file::add_task(contents, root, &section, &text)
+    })
+}
@@ -0,0 +1,44 @@
+use super::Context;
+use crate::cli::CarryArgs;
+use rusty_tasks::file;
+use rusty_tasks::{Error, Result};
+use similar::TextDiff;
+use std::fs::read_to_string;
+
+/// create today's file if needed and print its path, with `--dry-run` print
+/// what would be written instead
+pub fn carry(ctx: &Context, args: CarryArgs) -> Result<()> {
+    let file_path = ctx.filepath(&ctx.today);
+    let exists = file_path.exists();
+    let merge = args.merge || args.since.is_some() || ctx.cfg.merge_missed;
+
+    if !args.dry_run {
+        if exists {
+            log::info!("{} already exists", file_path.to_string_lossy());
+        } else {
+            let new_file = ctx.new_file(merge, args.since)?;
+            log::info!("writing to file: {}", file_path.to_string_lossy());
+            file::write_file(&file_path, &new_file.content)?;
+            eprint!("{}", new_file.summary());
+        }
+        println!("{}", file_path.to_string_lossy());
+        return Ok(());
+    }
+
+    let new_file = ctx.new_file(merge, args.since)?;
+    if exists {
+        let current = read_to_string(&file_path).map_err(|e| Error::io(&file_path, e))?;
+        let path = file_path.to_string_lossy();
+        let diff = TextDiff::from_lines(&current, &new_file.content);
+        print!(
+            "{}",
+            diff.unified_diff()
+                .header(&path, &format!("{} (carried over)", path))
+        );
+    } else {
+        print!("{}", new_file.content);
+    }
+    // the summary goes to stderr, keeping stdout for the file
+    eprint!("{}", new_file.summary());
+    Ok(())
+}
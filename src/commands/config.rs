@@ -0,0 +1,16 @@
+use crate::cli::ConfigArgs;
+use rusty_tasks::config::{Config, ConfigError};
+use rusty_tasks::Result;
+use std::path::Path;
+
+/// print the location of the config file or its loaded contents
+pub fn config(cfg_file: &Path, args: ConfigArgs) -> Result<()> {
+    if args.show {
+        let cfg = Config::load(cfg_file)?;
+        let text = serde_json::to_string_pretty(&cfg).map_err(ConfigError::Serialize)?;
+        println!("{}", text);
+    } else {
+        println!("{}", cfg_file.to_string_lossy());
+    }
+    Ok(())
+}
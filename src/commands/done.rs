@@ -0,0 +1,46 @@
+use super::{edit_file, Context};
+use crate::cli::SelectArgs;
+use rusty_tasks::file;
+use rusty_tasks::todo::{Status, Task};
+use rusty_tasks::{Error, Result};
+
+/// mark the selected task as done
+pub fn done(ctx: &Context, args: SelectArgs) -> Result<()> {
+    update_status(ctx, args, |_| Status::Done('x'))
+}
+
+/// flip the selected task between done and not done
+pub fn toggle(ctx: &Context, args: SelectArgs) -> Result<()> {
+    update_status(ctx, args, |status| match status {
+        Status::Done(_) => Status::Empty,
+        _ => Status::Done('x'),
+    })
+}
+
+fn update_status<F>(ctx: &Context, args: SelectArgs, next: F) -> Result<()>
+where
+    F: Fn(&Status) -> Status,
+{
+    let todo_file = ctx.resolve_file(&ctx.today)?;
+    edit_file(&todo_file, |contents, root| {
+        let node = match (&args.pattern, &args.section, &args.index) {
+            (Some(pattern), section, _) => {
+                let sections = match section {
+                    Some(section) => vec![section.clone()],
+                    None => ctx.cfg.sections.clone(),
+                };
+                file::match_task(root, &sections, pattern)?
+            }
+            (None, Some(section), Some(index)) => file::find_task(root, section, &index.0)?,
+            _ => return Err(Error::TaskNotFound("(no task selected)".into())),
+        };
+
+        let task = Task::try_from(node).map_err(|source| Error::Task {
+            path: todo_file.file.clone(),
+            source,
+        })?;
+        let status = next(&task.status);
+        log::info!("setting status of {:?} to {:?}", task.text, status);
+        file::replace_task_status(contents, node, &status)
+    })
+}
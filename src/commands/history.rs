@@ -0,0 +1,134 @@
+use super::Context;
+use crate::cli::{Format, HistoryArgs};
+use rusty_tasks::output::{to_json, TaskHistory};
+use rusty_tasks::todo::{task_history, Lineage, StatusMap, TaskState};
+use rusty_tasks::{Error, Result};
+
+/// print every day the tasks matching the query appeared on
+pub fn history(ctx: &Context, args: HistoryArgs) -> Result<()> {
+    let index = ctx.index()?;
+    let entries = index.entries();
+    let lineages = task_history(
+        entries
+            .iter()
+            .map(|entry| (&entry.file, entry.sections.as_slice())),
+        &args.query,
+    );
+    if lineages.is_empty() {
+        return Err(Error::TaskNotFound(args.query));
+    }
+
+    if args.format == Format::Json {
+        println!("{}", to_json(TaskHistory { lineages })?);
+        return Ok(());
+    }
+    let statuses = StatusMap::from(&ctx.cfg.statuses);
+    let timelines: Vec<String> = lineages
+        .iter()
+        .map(|lineage| format_lineage(lineage, &statuses))
+        .collect();
+    print!("{}", timelines.join("\n"));
+    Ok(())
+}
+
+/// timeline of a task: the day it was first seen, each day it was carried to
+/// and the day it was finished
+fn format_lineage(lineage: &Lineage, statuses: &StatusMap) -> String {
+    let Some(last) = lineage.appearances.last() else {
+        return String::new();
+    };
+    let mut output = format!("{}\n", last.task.text.trim());
+    let mut finished = None;
+    for (i, appearance) in lineage.appearances.iter().enumerate() {
+        let task = &appearance.task;
+        let state = statuses.status_state(&task.status);
+        let event = match state {
+            TaskState::Done => "done",
+            TaskState::Cancelled => "cancelled",
+            _ if i == 0 => "first seen",
+            _ => "carried",
+        };
+        if state.is_finished() && finished.is_none() {
+            finished = Some((event, task.dates.completed.unwrap_or(appearance.file.date)));
+        }
+        output.push_str(&format!(
+            "  {}  {:<10}  [{}] {} {}  {}:{}\n",
+            appearance.file.date,
+            event,
+            task.status.task_char().unwrap_or(' '),
+            appearance.section,
+            appearance.position,
+            appearance.file.file.to_string_lossy(),
+            task.line.unwrap_or_default()
+        ));
+    }
+
+    let first = &lineage.appearances[0];
+    let seen = first
+        .task
+        .dates
+        .created
+        .map_or(first.file.date, |created| created.min(first.file.date));
+    match finished {
+        Some((event, date)) => output.push_str(&format!(
+            "  first seen {}, {} {} after {} days\n",
+            seen,
+            event,
+            date,
+            (date - seen).num_days()
+        )),
+        None => output.push_str(&format!(
+            "  first seen {}, last seen {}, not finished\n",
+            seen, last.file.date
+        )),
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rusty_tasks::todo::{Appearance, File as TodoFile, Status, Task, TaskDates};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_format_lineage() {
+        let appearance = |date: &str, status, text: &str| Appearance {
+            file: TodoFile::try_from(PathBuf::from(format!("/notes/{}.md", date))).unwrap(),
+            section: "Daily".into(),
+            position: "1".into(),
+            task: Task {
+                status,
+                text: text.into(),
+                dates: TaskDates::parse(text),
+                line: Some(5),
+                ..Default::default()
+            },
+        };
+        let mut lineage = Lineage {
+            appearances: vec![
+                appearance("2024-01-02", Status::Empty, "write RFC ➕ 2024-01-01"),
+                appearance("2024-01-03", Status::Todo('/'), "write RFC ➕ 2024-01-01"),
+            ],
+        };
+        let statuses = StatusMap::default();
+        assert_eq!(
+            format_lineage(&lineage, &statuses),
+            "\
+write RFC ➕ 2024-01-01
+  2024-01-02  first seen  [ ] Daily 1  /notes/2024-01-02.md:5
+  2024-01-03  carried     [/] Daily 1  /notes/2024-01-03.md:5
+  first seen 2024-01-01, last seen 2024-01-03, not finished
+"
+        );
+
+        lineage.appearances.push(appearance(
+            "2024-01-05",
+            Status::Done('x'),
+            "write RFC ➕ 2024-01-01 ✅ 2024-01-04",
+        ));
+        let output = format_lineage(&lineage, &statuses);
+        assert!(output.contains("  2024-01-05  done        [x] Daily 1"));
+        assert!(output.ends_with("  first seen 2024-01-01, done 2024-01-04 after 3 days\n"));
+    }
+}
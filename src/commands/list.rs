@@ -0,0 +1,162 @@
+use super::{canonical, Context};
+use crate::cli::{Format, ListArgs, TagArgs};
+use rusty_tasks::index::{Index, IndexEntry};
+use rusty_tasks::output::{to_json, FileList, TagCounts};
+use rusty_tasks::todo::{File as TodoFile, Task};
+use rusty_tasks::{Error, Result};
+use std::collections::HashSet;
+
+/// list notes files, either all of them or the closest to a date
+pub fn list(ctx: &Context, args: ListArgs) -> Result<()> {
+    let mut files = ctx.files()?;
+    if args.count_tags {
+        return count_tags(ctx, &ctx.index()?, &args.tags, args.format);
+    }
+    if !args.tags.tags.is_empty() || !args.tags.contexts.is_empty() {
+        // only files with a task matching the tags and contexts
+        let index = ctx.index()?;
+        let matching: HashSet<_> = index
+            .entries()
+            .into_iter()
+            .filter(|entry| file_tasks(ctx, entry).any(|t| matches(t, &args.tags)))
+            .map(|entry| entry.file.file.clone())
+            .collect();
+        files.retain(|f| matching.contains(&f.file));
+    }
+
+    if args.format == Format::Json {
+        let mut files: Vec<TodoFile> = if args.all {
+            files
+        } else {
+            TodoFile::get_closest_files(files, args.date.target(&ctx.today), args.number)
+        };
+        if args.all {
+            files.sort_by_key(|f| f.date);
+        }
+        for f in files.iter_mut() {
+            f.file = f.file.canonicalize().map_err(|e| Error::io(&f.file, e))?;
+        }
+        println!("{}", to_json(FileList { files })?);
+        return Ok(());
+    }
+
+    if args.all {
+        for f in files {
+            println!("{}", canonical(&f.file)?);
+        }
+        return Ok(());
+    }
+
+    let target = args.date.target(&ctx.today);
+    let closest_files = TodoFile::get_closest_files(files, target, args.number);
+    println!("Today - n\tFile");
+    for f in closest_files {
+        println!(
+            "{}\t\t{}",
+            (ctx.today - f.date).num_days(),
+            canonical(&f.file)?
+        );
+    }
+    Ok(())
+}
+
+/// print the number of tasks with each tag and context across all notes,
+/// counting only tasks matching `filter`
+fn count_tags(ctx: &Context, index: &Index, filter: &TagArgs, format: Format) -> Result<()> {
+    let mut counts = TagCounts::default();
+    for entry in index.entries() {
+        for task in file_tasks(ctx, entry) {
+            count_matching(&mut counts, task, filter);
+        }
+    }
+
+    if format == Format::Json {
+        println!("{}", to_json(counts)?);
+        return Ok(());
+    }
+    println!("Tasks\tTag");
+    for (tag, n) in &counts.tags {
+        println!("{}\t#{}", n, tag);
+    }
+    for (context, n) in &counts.contexts {
+        println!("{}\t@{}", n, context);
+    }
+    Ok(())
+}
+
+/// Count the tags and contexts of `task` and its subtasks that match
+/// `filter` themselves, a parent matched through one of its subtasks is not
+/// counted
+fn count_matching(counts: &mut TagCounts, task: &Task, filter: &TagArgs) {
+    if has_all(task, filter) {
+        counts.add_own(task);
+    }
+    for subtask in task.subtasks.iter().flatten() {
+        count_matching(counts, subtask, filter);
+    }
+}
+
+/// whether `task` itself has all the tags and contexts
+fn has_all(task: &Task, filter: &TagArgs) -> bool {
+    filter.tags.iter().all(|tag| task.has_tag(tag))
+        && filter
+            .contexts
+            .iter()
+            .all(|context| task.has_context(context))
+}
+
+/// whether `task` or one of its subtasks has all the tags and contexts
+fn matches(task: &Task, filter: &TagArgs) -> bool {
+    has_all(task, filter) || task.subtasks.iter().flatten().any(|t| matches(t, filter))
+}
+
+/// top level tasks in the configured sections of an indexed notes file
+fn file_tasks<'a>(ctx: &'a Context, entry: &'a IndexEntry) -> impl Iterator<Item = &'a Task> {
+    entry
+        .sections
+        .iter()
+        .filter(|group| ctx.cfg.sections.contains(&group.name))
+        .flat_map(|group| &group.tasks)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_count_matching() {
+        let task = Task {
+            tags: vec!["work".into()],
+            contexts: vec!["office".into()],
+            subtasks: Some(vec![
+                Task {
+                    tags: vec!["docs".into()],
+                    ..Default::default()
+                },
+                Task {
+                    tags: vec!["docs".into(), "Work".into()],
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        };
+        let filter = |tags: &[&str]| TagArgs {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            contexts: Vec::new(),
+        };
+
+        let mut counts = TagCounts::default();
+        count_matching(&mut counts, &task, &filter(&[]));
+        assert_eq!(counts.tags["docs"], 2);
+        assert_eq!(counts.tags["work"], 2);
+        assert_eq!(counts.contexts["office"], 1);
+
+        // the parent only matches through its subtask and is not counted
+        let mut counts = TagCounts::default();
+        assert!(matches(&task, &filter(&["docs"])));
+        count_matching(&mut counts, &task, &filter(&["docs"]));
+        assert_eq!(counts.tags["docs"], 2);
+        assert_eq!(counts.tags["work"], 1);
+        assert!(counts.contexts.is_empty());
+    }
+}
@@ -0,0 +1,389 @@
+mod add;
+mod carry;
+mod config;
+mod done;
+mod history;
+mod list;
+mod open;
+mod review;
+mod search;
+mod show;
+mod stats;
+
+pub use add::add;
+pub use carry::carry;
+pub use config::config;
+pub use done::{done, toggle};
+pub use history::history;
+pub use list::list;
+pub use open::open;
+pub use review::review;
+pub use search::search;
+pub use show::show;
+pub use stats::stats;
+
+use chrono::{Local, NaiveDate};
+use comrak::nodes::AstNode;
+use comrak::Arena;
+use resolve_path::PathResolveExt;
+use rusty_tasks::config::Config;
+use rusty_tasks::file::{self, CarryOptions, CarryReport};
+use rusty_tasks::index::Index;
+use rusty_tasks::todo::File as TodoFile;
+use rusty_tasks::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// State shared by all commands
+pub struct Context {
+    pub cfg: Config,
+    pub data_dir: PathBuf,
+    pub today: NaiveDate,
+}
+
+/// Find the config file to use, writing the default config if none exist
+pub fn config_file(config: Option<String>) -> Result<PathBuf> {
+    // getting config location
+    let expected_cfg_files = Config::expected_locations()?;
+
+    // getting exising config files
+    let cfg_files: Vec<&Path> = expected_cfg_files
+        .iter()
+        .map(Path::new)
+        .filter(|file| file.exists())
+        .collect();
+
+    // writing default config if non exist
+    if cfg_files.is_empty() && config.is_none() {
+        Config::write_default(&expected_cfg_files[0])?;
+    }
+
+    // set witch config file to load
+    Ok(match config {
+        Some(file) => PathBuf::from(file),
+        None => match cfg_files.last() {
+            None => expected_cfg_files[0].clone(),
+            Some(file) => file.to_path_buf(),
+        },
+    })
+}
+
+impl Context {
+    pub fn load(cfg_file: &Path) -> Result<Self> {
+        let cfg = Config::load(cfg_file)?;
+        log::debug!("{:#?}", cfg);
+
+        // resolve data directory and create it if it does not exisit
+        let data_dir = cfg.notes_dir.resolve().to_path_buf();
+        if fs::metadata(&data_dir).is_err() {
+            fs::create_dir_all(&data_dir).map_err(|e| Error::io(&data_dir, e))?;
+            log::info!("created dir {}", &data_dir.to_string_lossy());
+        }
+
+        Ok(Context {
+            cfg,
+            data_dir,
+            today: Local::now().date_naive(),
+        })
+    }
+
+    /// all notes files ordered by date, found in the notes directory by the
+    /// configured file pattern, and in its subdirectories when the pattern
+    /// has any
+    pub fn files(&self) -> Result<Vec<TodoFile>> {
+        let depth = self.cfg.file_pattern.split('/').count();
+        let mut files: Vec<TodoFile> = file::list_files(&self.data_dir, depth)?
+            .into_iter()
+            .filter_map(|f| TodoFile::parse(f, &self.cfg.file_pattern).ok())
+            .collect();
+        files.sort_by_key(|f| f.date);
+        Ok(files)
+    }
+
+    /// path of the notes file for `date`
+    pub fn filepath(&self, date: &NaiveDate) -> PathBuf {
+        file::get_filepath(&self.data_dir, date, &self.cfg.file_pattern)
+    }
+
+    /// Parsed tasks of every notes file. Only files that changed since they
+    /// were last indexed are parsed, the index is saved in the notes
+    /// directory unless it is disabled in the config.
+    pub fn index(&self) -> Result<Index> {
+        let files = self.files()?;
+        let mut index = if self.cfg.index {
+            Index::load(&self.data_dir)
+        } else {
+            Index::default()
+        };
+        if index.update(&self.data_dir, &files)? && self.cfg.index {
+            index.save(&self.data_dir)?;
+        }
+        Ok(index)
+    }
+
+    /// Existing notes file closest to `target`, none when `target` is today
+    /// and its file does not exist yet
+    fn closest_file(&self, target: &NaiveDate) -> Result<Option<TodoFile>> {
+        let today = &self.today;
+        let closest_files = TodoFile::get_closest_files(self.files()?, *target, 1);
+        // TODO: If the user did not pick a date that exist they should have the
+        // option to updated their choice
+        Ok(closest_files
+            .into_iter()
+            .next()
+            .filter(|todo_file| todo_file.date >= *today || target != today))
+    }
+
+    /// Notes file closest to `target`. When `target` is today and its file
+    /// does not exist yet it is created, carrying over tasks from the most
+    /// recent notes.
+    pub fn resolve_file(&self, target: &NaiveDate) -> Result<TodoFile> {
+        if let Some(todo_file) = self.closest_file(target)? {
+            return Ok(todo_file);
+        }
+
+        // create today's file if the user specifies today's notes but it
+        // does not exist
+        let today = &self.today;
+        let new_file = self.new_file(self.cfg.merge_missed, None)?;
+        if !new_file.sources.is_empty() {
+            log::info!("{}", new_file.summary());
+        }
+        let file_path = self.filepath(today);
+        log::info!("writing to file: {}", file_path.to_string_lossy());
+        file::write_file(&file_path, &new_file.content)?;
+        Ok(TodoFile::parse(file_path, &self.cfg.file_pattern)?)
+    }
+
+    /// Same as [`Context::resolve_file`] along with the contents of the file,
+    /// without writing anything. Today's file, when it does not exist yet, is
+    /// what `resolve_file` would create.
+    pub fn read_file(&self, target: &NaiveDate) -> Result<(TodoFile, String)> {
+        if let Some(todo_file) = self.closest_file(target)? {
+            let contents = file::load_file(&todo_file)?;
+            return Ok((todo_file, contents));
+        }
+
+        let new_file = self.new_file(self.cfg.merge_missed, None)?;
+        let todo_file = TodoFile {
+            file: self.filepath(&self.today),
+            date: self.today,
+        };
+        Ok((todo_file, new_file.content))
+    }
+
+    /// Carry-over options from the config, along with the contents of the
+    /// template when one is configured and the IDs already used when IDs are
+    /// assigned
+    fn carry_options(&self) -> Result<CarryOptions> {
+        let mut options = CarryOptions::from(&self.cfg);
+        if options.assign_ids {
+            options.known_ids = self.index()?.ids();
+        }
+        if let Some(template) = &self.cfg.template {
+            let path = template.resolve();
+            log::info!("using template {}", path.to_string_lossy());
+            options.template = Some(fs::read_to_string(&path).map_err(|e| Error::io(&*path, e))?);
+        }
+        Ok(options)
+    }
+
+    /// Generate the contents of today's file without writing it. With
+    /// `merge`, unfinished tasks are merged from every file since `since`,
+    /// or since the last merge when it is not given.
+    pub fn new_file(&self, merge: bool, since: Option<NaiveDate>) -> Result<NewFile> {
+        let today = &self.today;
+        let sections = &self.cfg.sections;
+        let mut previous: Vec<TodoFile> = self
+            .files()?
+            .into_iter()
+            .filter(|f| f.date < *today)
+            .collect();
+        previous.sort_by_key(|f| std::cmp::Reverse(f.date));
+
+        let options = self.carry_options()?;
+
+        let Some(newest) = previous.first() else {
+            // no note files exist creating based on template from config
+            log::info!("creating new empty file with sections: {:?}", sections);
+            return Ok(NewFile {
+                content: file::empty_file(today, &options)?,
+                sources: Vec::new(),
+                report: CarryReport::default(),
+                no_earlier_merge: false,
+            });
+        };
+
+        // attempt to load file
+        log::info!("looking for sections: {:?}", sections);
+        log::info!(
+            "loading and parsing file: {}",
+            newest.file.to_string_lossy()
+        );
+        let contents = file::load_file(newest)?;
+        log::trace!("file loaded");
+
+        if !merge {
+            // copy the most recent notes before today
+            let (content, report) =
+                file::carry_over_with_report(&contents, &newest.date, today, &options)?;
+            return Ok(NewFile {
+                content,
+                sources: vec![newest.clone()],
+                report,
+                no_earlier_merge: false,
+            });
+        }
+
+        // files after the last merge, read from the marker of the most recent
+        // file or else found by going back to the last file with a marker
+        let since = since.or_else(|| file::carried_from(&contents).and_then(|d| d.succ_opt()));
+        let mut sources = vec![(newest.clone(), contents)];
+        let mut found_merge = since.is_some();
+        for todo_file in previous.iter().skip(1) {
+            if since.is_some_and(|since| todo_file.date < since) {
+                break;
+            }
+            log::info!(
+                "loading and parsing file: {}",
+                todo_file.file.to_string_lossy()
+            );
+            let contents = file::load_file(todo_file)?;
+            let merged = file::carried_from(&contents).is_some();
+            sources.push((todo_file.clone(), contents));
+            if since.is_none() && merged {
+                // it already holds the unfinished tasks of the files before it
+                found_merge = true;
+                break;
+            }
+        }
+        if !found_merge {
+            log::warn!("no earlier merge found, only merging the most recent file");
+            sources.truncate(1);
+        }
+
+        let contents: Vec<_> = sources
+            .iter()
+            .map(|(todo_file, contents)| (todo_file.date, contents.clone()))
+            .collect();
+        let (content, report) = file::carry_over_merged(&contents, today, &options)?;
+        Ok(NewFile {
+            content,
+            sources: sources
+                .into_iter()
+                .map(|(todo_file, _)| todo_file)
+                .collect(),
+            report,
+            no_earlier_merge: !found_merge,
+        })
+    }
+}
+
+/// Contents of a new file for today and where its tasks were carried from
+pub struct NewFile {
+    pub content: String,
+    /// files tasks were carried from, newest first
+    pub sources: Vec<TodoFile>,
+    pub report: CarryReport,
+    /// merging found no file left by an earlier merge, so only the most
+    /// recent file was used
+    pub no_earlier_merge: bool,
+}
+
+impl NewFile {
+    /// where tasks were carried from and how many
+    pub fn summary(&self) -> String {
+        if self.sources.is_empty() {
+            return "no previous notes to carry over from\n".into();
+        }
+        let files: Vec<_> = self
+            .sources
+            .iter()
+            .map(|f| f.file.to_string_lossy())
+            .collect();
+        let mut summary = format!("carried over from {}\n", files.join(", "));
+        if self.no_earlier_merge {
+            summary.push_str("no earlier merge found, use --since to merge older files\n");
+        }
+        summary.push_str(&self.report.to_string());
+        summary
+    }
+}
+
+/// Open `file` in the configured editor
+fn open_in_editor(ctx: &Context, file: &Path) -> Result<()> {
+    log::info!("Opening {} in {}", file.to_string_lossy(), ctx.cfg.editor);
+    Command::new(&ctx.cfg.editor)
+        .arg(file)
+        .status()
+        .map_err(|source| Error::Editor {
+            editor: ctx.cfg.editor.clone(),
+            source,
+        })?;
+    Ok(())
+}
+
+/// Parse a notes file, let `edit` change its contents and write them back.
+/// `edit` gets the contents along with their document tree, and is expected
+/// to leave the parts it does not change as they are.
+fn edit_file<F>(todo_file: &TodoFile, edit: F) -> Result<()>
+where
+    F: for<'a> FnOnce(&str, &'a AstNode<'a>) -> Result<String>,
+{
+    let contents = file::load_file(todo_file)?;
+    let arena = Arena::new();
+    let root = file::parse_todo_file(&contents, &arena);
+    let contents = edit(&contents, root)?;
+
+    log::info!("writing to file: {}", todo_file.file.to_string_lossy());
+    file::write_file(&todo_file.file, &contents)
+}
+
+fn canonical(path: &Path) -> Result<String> {
+    path.canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| Error::io(path, e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_resolve_file() {
+        let data_dir = temp_dir().join(format!("rusty-tasks-resolve-{}", std::process::id()));
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let ctx = Context {
+            cfg: Config {
+                sections: vec!["Daily".into()],
+                file_pattern: "%Y/%m/%Y-%m-%d.md".into(),
+                ..Config::default()
+            },
+            data_dir: data_dir.clone(),
+            today: date(3),
+        };
+        file::write_file(
+            &data_dir.join("2024/01/2024-01-01.md"),
+            "# Today's tasks 2024-01-01\n\n## Daily\n\n- [ ] task\n",
+        )
+        .unwrap();
+        // files outside of the pattern's layout are not notes
+        file::write_file(&data_dir.join("2024-01-02.md"), "## Daily\n\n- [ ] other\n").unwrap();
+
+        let (todo_file, contents) = ctx.read_file(&date(3)).unwrap();
+        assert_eq!(todo_file.file, data_dir.join("2024/01/2024-01-03.md"));
+        assert!(!todo_file.file.exists());
+        assert!(contents.contains("- [ ] task\n"));
+        assert!(!contents.contains("other"));
+
+        let todo_file = ctx.resolve_file(&date(3)).unwrap();
+        assert_eq!(todo_file.file, data_dir.join("2024/01/2024-01-03.md"));
+        assert_eq!(todo_file.date, date(3));
+        assert_eq!(fs::read_to_string(&todo_file.file).unwrap(), contents);
+        assert_eq!(ctx.resolve_file(&date(2)).unwrap().date, date(1));
+        assert_eq!(ctx.files().unwrap().len(), 2);
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
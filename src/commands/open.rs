@@ -0,0 +1,9 @@
+use super::{open_in_editor, Context};
+use crate::cli::DateArgs;
+use rusty_tasks::Result;
+
+/// open the selected notes file in the configured editor
+pub fn open(ctx: &Context, args: DateArgs) -> Result<()> {
+    let current_file = ctx.resolve_file(&args.target(&ctx.today))?.file;
+    open_in_editor(ctx, &current_file)
+}
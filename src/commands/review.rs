@@ -0,0 +1,32 @@
+use super::{open_in_editor, Context};
+use crate::cli::ReviewArgs;
+use rusty_tasks::file::{self, ReviewPeriod};
+use rusty_tasks::todo::StatusMap;
+use rusty_tasks::Result;
+
+/// write the review of a week or month unless it exists and open it in the
+/// editor
+pub fn review(ctx: &Context, args: ReviewArgs) -> Result<()> {
+    let date = args.date.unwrap_or(ctx.today);
+    let period = match args.month {
+        true => ReviewPeriod::month(&date),
+        false => ReviewPeriod::week(&date),
+    };
+    let path = file::review_filepath(&ctx.data_dir, &period, &ctx.cfg.file_pattern);
+
+    if args.force || !path.exists() {
+        let index = ctx.index()?;
+        let entries = index.entries();
+        let content = file::create_review(
+            entries
+                .iter()
+                .map(|entry| (&entry.file, entry.sections.as_slice())),
+            &period,
+            &ctx.cfg.sections,
+            &StatusMap::from(&ctx.cfg.statuses),
+        )?;
+        log::info!("writing to file: {}", path.to_string_lossy());
+        file::write_file(&path, &content)?;
+    }
+    open_in_editor(ctx, &path)
+}
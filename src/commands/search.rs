@@ -0,0 +1,142 @@
+use super::Context;
+use crate::cli::{Format, SearchArgs};
+use rusty_tasks::output::{to_json, SearchMatch, SearchResults};
+use rusty_tasks::todo::{StatusMap, Task};
+use rusty_tasks::Result;
+
+/// print the tasks of every notes file matching the search
+pub fn search(ctx: &Context, args: SearchArgs) -> Result<()> {
+    let index = ctx.index()?;
+    let entries = index
+        .entries()
+        .into_iter()
+        .filter(|entry| args.since.is_none_or(|since| entry.file.date >= since))
+        .filter(|entry| args.until.is_none_or(|until| entry.file.date <= until));
+
+    let sections = if args.section.is_empty() {
+        &ctx.cfg.sections
+    } else {
+        &args.section
+    };
+    let query = args.query.as_deref().map(str::to_lowercase);
+    let statuses = StatusMap::from(&ctx.cfg.statuses);
+    let matches = |task: &Task| {
+        query
+            .as_ref()
+            .is_none_or(|query| task.text.to_lowercase().contains(query))
+            && (args.states.is_empty()
+                || args.states.contains(&statuses.status_state(&task.status)))
+            && args.tags.tags.iter().all(|tag| task.has_tag(tag))
+            && args.tags.contexts.iter().all(|c| task.has_context(c))
+    };
+
+    let mut results = Vec::new();
+    for entry in entries {
+        for section in sections {
+            let Some(group) = entry.sections.iter().find(|g| g.name == *section) else {
+                continue;
+            };
+            let mut found = Vec::new();
+            find_matches(&group.tasks, "", &matches, &mut found);
+            results.extend(found.into_iter().map(|(position, task)| SearchMatch {
+                file: entry.file.clone(),
+                line: task.line.unwrap_or_default(),
+                section: section.clone(),
+                position,
+                task: task.clone(),
+            }));
+        }
+    }
+
+    if args.format == Format::Json {
+        println!("{}", to_json(SearchResults { matches: results })?);
+        return Ok(());
+    }
+    for result in results {
+        println!("{}", format_match(&result));
+    }
+    Ok(())
+}
+
+/// tasks and subtasks matching `matches` along with their dotted position
+fn find_matches<'a, F>(
+    tasks: &'a [Task],
+    parent: &str,
+    matches: &F,
+    found: &mut Vec<(String, &'a Task)>,
+) where
+    F: Fn(&Task) -> bool,
+{
+    for (i, task) in tasks.iter().enumerate() {
+        let position = format!("{}{}", parent, i + 1);
+        if matches(task) {
+            found.push((position.clone(), task));
+        }
+        if let Some(subtasks) = &task.subtasks {
+            find_matches(subtasks, &format!("{}.", position), matches, found);
+        }
+    }
+}
+
+/// `file:line` followed by the date, location and task, so editors can jump
+/// to it
+fn format_match(result: &SearchMatch) -> String {
+    format!(
+        "{}:{}: {} {} {} [{}] {}",
+        result.file.file.to_string_lossy(),
+        result.line,
+        result.file.date,
+        result.section,
+        result.position,
+        result.task.status.task_char().unwrap_or(' '),
+        result.task.text.trim()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rusty_tasks::todo::{File as TodoFile, Status};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_search() {
+        let result = SearchMatch {
+            file: TodoFile::try_from(PathBuf::from("/notes/2024-01-02.md")).unwrap(),
+            line: 7,
+            section: "Weekly".into(),
+            position: "2.1".into(),
+            task: Task {
+                status: Status::Todo('/'),
+                text: "deploy ".into(),
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            format_match(&result),
+            "/notes/2024-01-02.md:7: 2024-01-02 Weekly 2.1 [/] deploy"
+        );
+
+        let task = |text: &str, subtasks| Task {
+            text: text.into(),
+            subtasks,
+            ..Default::default()
+        };
+        let tasks = vec![
+            task(
+                "deploy app",
+                Some(vec![task("db", None), task("deploy db", None)]),
+            ),
+            task("docs", None),
+        ];
+        let mut found = Vec::new();
+        find_matches(
+            &tasks,
+            "",
+            &|t: &Task| t.text.contains("deploy"),
+            &mut found,
+        );
+        let positions: Vec<&str> = found.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(positions, vec!["1", "1.2"]);
+    }
+}
@@ -0,0 +1,370 @@
+use super::Context;
+use crate::cli::{Format, ShowArgs};
+use chrono::NaiveDate;
+use clap::ColorChoice;
+use comrak::Arena;
+use rusty_tasks::file;
+use rusty_tasks::output::{to_json, FileTasks};
+use rusty_tasks::todo::{Status, StatusMap, Task, TaskGroup, TaskState};
+use rusty_tasks::Result;
+use std::env::var_os;
+use std::io::{stdout, IsTerminal};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const DIM: &str = "\x1b[2m";
+
+/// How tasks are printed
+struct Style {
+    /// hide finished tasks
+    pending: bool,
+    color: bool,
+    /// highlight unfinished tasks older than this many days
+    stale_after: Option<u32>,
+    today: NaiveDate,
+    filter: Filter,
+    /// meaning of status characters
+    statuses: StatusMap,
+}
+
+/// Which top level tasks are printed
+#[derive(Default)]
+struct Filter {
+    /// only tasks due on or before this day
+    due_by: Option<NaiveDate>,
+    /// only tasks scheduled on or before this day
+    scheduled_by: Option<NaiveDate>,
+    /// hide tasks that can not be started before this day
+    available_on: Option<NaiveDate>,
+    /// only tasks with all of these tags
+    tags: Vec<String>,
+    /// only tasks with all of these contexts
+    contexts: Vec<String>,
+}
+
+impl Filter {
+    fn matches(&self, task: &Task) -> bool {
+        let dates = &task.dates;
+        let by = |date: Option<NaiveDate>, limit: Option<NaiveDate>| match limit {
+            Some(limit) => date.is_some_and(|date| date <= limit),
+            None => true,
+        };
+        by(dates.due, self.due_by)
+            && by(dates.scheduled, self.scheduled_by)
+            && self.tags.iter().all(|tag| task.has_tag(tag))
+            && self
+                .contexts
+                .iter()
+                .all(|context| task.has_context(context))
+            && self
+                .available_on
+                .is_none_or(|day| dates.start.is_none_or(|start| start <= day))
+    }
+}
+
+/// print the tasks in the selected file
+pub fn show(ctx: &Context, args: ShowArgs) -> Result<()> {
+    // show is used in prompts, so it never writes today's file
+    let (todo_file, contents) = ctx.read_file(&args.date.target(&ctx.today))?;
+    let arena = Arena::new();
+    let root = file::parse_todo_file(&contents, &arena);
+
+    let sections = if args.section.is_empty() {
+        &ctx.cfg.sections
+    } else {
+        &args.section
+    };
+    let groups = file::find_task_groups(root, sections);
+    let filter = Filter {
+        due_by: args.due_by,
+        scheduled_by: args.scheduled_by,
+        available_on: args.available.then_some(ctx.today),
+        tags: args.tags.tags,
+        contexts: args.tags.contexts,
+    };
+
+    if args.format == Format::Json {
+        let mut sections = groups;
+        for group in sections.iter_mut() {
+            group.tasks.retain(|task| filter.matches(task));
+        }
+        if args.pending {
+            let statuses = StatusMap::from(&ctx.cfg.statuses);
+            sections
+                .iter_mut()
+                .for_each(|group| retain_pending(&mut group.tasks, &statuses));
+        }
+        println!(
+            "{}",
+            to_json(FileTasks {
+                file: todo_file,
+                sections
+            })?
+        );
+        return Ok(());
+    }
+
+    let color = match args.color {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => stdout().is_terminal() && var_os("NO_COLOR").is_none(),
+    };
+
+    let style = Style {
+        pending: args.pending,
+        color,
+        stale_after: args.stale_after.or(ctx.cfg.stale_after_days),
+        today: ctx.today,
+        filter,
+        statuses: StatusMap::from(&ctx.cfg.statuses),
+    };
+    for group in groups {
+        print!("{}", format_group(&group, &style));
+    }
+    Ok(())
+}
+
+fn format_group(group: &TaskGroup, style: &Style) -> String {
+    let mut output = if style.color {
+        format!("{}{}{}\n", BOLD, group.name, RESET)
+    } else {
+        format!("{}\n", group.name)
+    };
+    format_tasks(&mut output, &group.tasks, "", style);
+    output
+}
+
+/// remove finished tasks and subtasks
+fn retain_pending(tasks: &mut Vec<Task>, statuses: &StatusMap) {
+    tasks.retain(|task| !statuses.status_state(&task.status).is_finished());
+    for task in tasks.iter_mut() {
+        if let Some(subtasks) = &mut task.subtasks {
+            retain_pending(subtasks, statuses);
+        }
+    }
+}
+
+/// format tasks with their position so they can be passed to `done`
+fn format_tasks(output: &mut String, tasks: &[Task], parent: &str, style: &Style) {
+    let indent = "  ".repeat(parent.matches('.').count() + 1);
+    for (i, task) in tasks.iter().enumerate() {
+        let state = style.statuses.status_state(&task.status);
+        let done = state.is_finished();
+        if (style.pending && done) || (parent.is_empty() && !style.filter.matches(task)) {
+            continue;
+        }
+        let path = format!("{}{}", parent, i + 1);
+        let mut line = format!(
+            "{}{} [{}] {}",
+            indent,
+            path,
+            task.status.task_char().unwrap_or(' '),
+            task.text.trim()
+        );
+        let stale = match (task.age(&style.today), style.stale_after) {
+            (Some(age), Some(after)) if !done && age > after.into() => {
+                line.push_str(&format!(" ({} days old)", age));
+                true
+            }
+            _ => false,
+        };
+        let color = match (state, &task.status) {
+            _ if stale => Some(RED),
+            (TaskState::Done, _) => Some(GREEN),
+            (TaskState::Cancelled, _) => Some(DIM),
+            (TaskState::Open, Status::Empty) => None,
+            _ => Some(YELLOW),
+        };
+        let line = match color {
+            Some(color) if style.color => format!("{}{}{}", color, line, RESET),
+            _ => line,
+        };
+        output.push_str(&line);
+        output.push('\n');
+
+        if let Some(subtasks) = &task.subtasks {
+            format_tasks(output, subtasks, &format!("{}.", path), style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rusty_tasks::todo::{parse_contexts, parse_tags, TaskDates};
+
+    fn task(status: Status, text: &str, subtasks: Option<Vec<Task>>) -> Task {
+        Task {
+            status,
+            text: text.into(),
+            subtasks,
+            dates: TaskDates::parse(text),
+            tags: parse_tags(text),
+            contexts: parse_contexts(text),
+            ..Default::default()
+        }
+    }
+
+    fn style() -> Style {
+        Style {
+            pending: false,
+            color: false,
+            stale_after: None,
+            today: NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+            filter: Filter::default(),
+            statuses: StatusMap::default(),
+        }
+    }
+
+    /// group of `texts` as unchecked tasks
+    fn group_of(name: &str, texts: &[&str]) -> TaskGroup {
+        let mut group = TaskGroup::empty(name.into(), 2);
+        for text in texts {
+            group.tasks.push(task(Status::Empty, text, None));
+        }
+        group
+    }
+
+    fn daily() -> TaskGroup {
+        TaskGroup {
+            name: "Daily".into(),
+            tasks: vec![
+                task(Status::Done('x'), "done", None),
+                task(
+                    Status::Todo('/'),
+                    "in progress",
+                    Some(vec![
+                        task(Status::Empty, "first", None),
+                        task(Status::Done('X'), "second", None),
+                    ]),
+                ),
+                task(Status::Empty, "todo", None),
+            ],
+            level: 2,
+        }
+    }
+
+    #[test]
+    fn test_format_group() {
+        let group = daily();
+        let mut style = style();
+        assert_eq!(
+            format_group(&group, &style),
+            "\
+Daily
+  1 [x] done
+  2 [/] in progress
+    2.1 [ ] first
+    2.2 [X] second
+  3 [ ] todo
+"
+        );
+        style.pending = true;
+        assert_eq!(
+            format_group(&group, &style),
+            "\
+Daily
+  2 [/] in progress
+    2.1 [ ] first
+  3 [ ] todo
+"
+        );
+        style.pending = false;
+        style.color = true;
+        assert_eq!(
+            format_group(&TaskGroup::empty("Weekly".into(), 2), &style),
+            "\x1b[1mWeekly\x1b[0m\n"
+        );
+        assert!(format_group(&group, &style).contains("\x1b[32m  1 [x] done\x1b[0m\n"));
+    }
+
+    #[test]
+    fn test_format_stale() {
+        let aged = group_of(
+            "Weekly",
+            &["stale ➕ 2024-01-01", "fresh ➕ 2024-01-05", "unknown"],
+        );
+        let mut style = style();
+        style.color = true;
+        style.stale_after = Some(7);
+        let output = format_group(&aged, &style);
+        assert!(output.contains("\x1b[31m  1 [ ] stale ➕ 2024-01-01 (9 days old)\x1b[0m\n"));
+        assert!(output.contains("  2 [ ] fresh ➕ 2024-01-05\n"));
+        assert!(output.contains("  3 [ ] unknown\n"));
+    }
+
+    #[test]
+    fn test_filter_dates() {
+        let dated = group_of(
+            "Daily",
+            &[
+                "due 📅 2024-01-09",
+                "later due:2024-01-20 🛫 2024-01-15",
+                "scheduled ⏳ 2024-01-10",
+            ],
+        );
+        let mut style = style();
+        style.filter.due_by = NaiveDate::from_ymd_opt(2024, 1, 10);
+        assert_eq!(
+            format_group(&dated, &style),
+            "Daily\n  1 [ ] due 📅 2024-01-09\n"
+        );
+        style.filter.due_by = None;
+        style.filter.scheduled_by = NaiveDate::from_ymd_opt(2024, 1, 10);
+        assert_eq!(
+            format_group(&dated, &style),
+            "Daily\n  3 [ ] scheduled ⏳ 2024-01-10\n"
+        );
+        style.filter.scheduled_by = None;
+        style.filter.available_on = Some(style.today);
+        assert!(!format_group(&dated, &style).contains("later"));
+    }
+
+    #[test]
+    fn test_filter_tags() {
+        let tagged = group_of("Daily", &["deploy #work @office", "call mom @phone"]);
+        let mut style = style();
+        style.filter.tags = vec!["#Work".into()];
+        assert_eq!(
+            format_group(&tagged, &style),
+            "Daily\n  1 [ ] deploy #work @office\n"
+        );
+        style.filter.contexts = vec!["phone".into()];
+        assert_eq!(format_group(&tagged, &style), "Daily\n");
+    }
+
+    #[test]
+    fn test_format_statuses() {
+        let group = daily();
+        let mut style = style();
+        style.color = true;
+        style.statuses = StatusMap::new([('/', TaskState::Cancelled)].into());
+        let output = format_group(&group, &style);
+        assert!(output.contains("\x1b[2m  2 [/] in progress\x1b[0m\n"));
+        style.pending = true;
+        assert_eq!(
+            format_group(&group, &style),
+            "\x1b[1mDaily\x1b[0m\n  3 [ ] todo\n"
+        );
+    }
+
+    #[test]
+    fn test_retain_pending() {
+        let mut tasks = daily().tasks;
+        retain_pending(&mut tasks, &StatusMap::default());
+        assert_eq!(
+            tasks,
+            vec![
+                task(
+                    Status::Todo('/'),
+                    "in progress",
+                    Some(vec![task(Status::Empty, "first", None)]),
+                ),
+                task(Status::Empty, "todo", None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use super::Context;
+use crate::cli::{Format, StatsArgs};
+use rusty_tasks::output::to_json;
+use rusty_tasks::stats::{bar, sparkline, Glyphs, Stats};
+use rusty_tasks::todo::StatusMap;
+use rusty_tasks::Result;
+
+/// print statistics about the tasks of all notes files
+pub fn stats(ctx: &Context, args: StatsArgs) -> Result<()> {
+    let index = ctx.index()?;
+    let entries = index.entries();
+    let stats = Stats::compute(
+        entries
+            .iter()
+            .map(|entry| (&entry.file, entry.sections.as_slice())),
+        &ctx.cfg.sections,
+        &StatusMap::from(&ctx.cfg.statuses),
+        args.since,
+    );
+
+    if args.format == Format::Json {
+        println!("{}", to_json(stats)?);
+        return Ok(());
+    }
+    let glyphs = match args.ascii {
+        true => Glyphs::ASCII,
+        false => Glyphs::UNICODE,
+    };
+    let days = match args.chart {
+        true => format_charts(&stats, glyphs),
+        false => format_days(&stats),
+    };
+    print!("{}", format_stats(&stats));
+    if !stats.days.is_empty() {
+        print!("\n{}", days);
+    }
+    Ok(())
+}
+
+/// Longest bar drawn by `format_charts`
+const BAR_WIDTH: usize = 40;
+
+/// summary followed by a table of the sections
+fn format_stats(stats: &Stats) -> String {
+    let mut output = String::new();
+    let days = stats.days.len();
+    output.push_str(&format!("{:<18}{}", "days with notes", days));
+    if let Some(since) = stats.since {
+        output.push_str(&format!(" (since {})", since));
+    }
+    output.push('\n');
+    if days == 0 {
+        return output;
+    }
+
+    let completed = stats.completed();
+    output.push_str(&format!(
+        "{:<18}{} ({:.1} per day)\n",
+        "completed",
+        completed,
+        completed as f64 / days as f64
+    ));
+    if let Some(average) = stats.average_carry_over {
+        output.push_str(&format!(
+            "{:<18}{:.1} times on average before done\n",
+            "carried over", average
+        ));
+    }
+    if let Some(streak) = &stats.longest_streak {
+        let days = match streak.days {
+            1 => "1 day".to_string(),
+            n => format!("{} days", n),
+        };
+        output.push_str(&format!(
+            "{:<18}{} ({} to {})\n",
+            "longest streak", days, streak.start, streak.end
+        ));
+    }
+    if let Some(oldest) = &stats.oldest_open {
+        output.push_str(&format!(
+            "{:<18}{} ({}, first seen {}, {} days)\n",
+            "oldest open task",
+            oldest.task.text.trim(),
+            oldest.section,
+            oldest.first_seen,
+            oldest.age
+        ));
+    }
+
+    let width = label_width(stats);
+    output.push_str(&format!(
+        "\n{:<width$}  {:>5}  {:>9}  {:>4}\n",
+        "section", "tasks", "completed", "rate"
+    ));
+    for section in &stats.sections {
+        let rate = section
+            .completion_rate
+            .map_or("-".to_string(), |rate| format!("{:.0}%", rate * 100.0));
+        output.push_str(&format!(
+            "{:<width$}  {:>5}  {:>9}  {:>4}\n",
+            section.name, section.tasks, section.completed, rate
+        ));
+    }
+
+    output
+}
+
+/// width of the first column of the tables, fitting section names and dates
+fn label_width(stats: &Stats) -> usize {
+    stats
+        .sections
+        .iter()
+        .map(|section| section.name.chars().count())
+        .chain(["section".len(), "YYYY-MM-DD".len()])
+        .max()
+        .unwrap_or_default()
+}
+
+/// table of the open and completed tasks of each day
+fn format_days(stats: &Stats) -> String {
+    let width = label_width(stats);
+    let mut output = format!("{:<width$}  {:>5}  {:>9}\n", "date", "open", "completed");
+    for day in &stats.days {
+        output.push_str(&format!(
+            "{:<width$}  {:>5}  {:>9}\n",
+            day.date.to_string(),
+            day.open.iter().sum::<usize>(),
+            day.completed
+        ));
+    }
+    output
+}
+
+/// bar chart of the tasks completed each day and a sparkline of the open
+/// tasks of each section over time, with how much that changed
+fn format_charts(stats: &Stats, glyphs: Glyphs) -> String {
+    let width = label_width(stats);
+    let max = stats
+        .days
+        .iter()
+        .map(|day| day.completed)
+        .max()
+        .unwrap_or_default();
+    let mut output = format!("{:<width$}  completed\n", "date");
+    for day in &stats.days {
+        let bar = bar(day.completed, max, BAR_WIDTH, glyphs);
+        let gap = if bar.is_empty() { "" } else { " " };
+        output.push_str(&format!(
+            "{:<width$}  {}{}{}\n",
+            day.date.to_string(),
+            bar,
+            gap,
+            day.completed
+        ));
+    }
+
+    let (Some(first), Some(last)) = (stats.days.first(), stats.days.last()) else {
+        return output;
+    };
+    output.push_str(&format!(
+        "\n{:<width$}  {} to {}\n",
+        "open tasks", first.date, last.date
+    ));
+    for (i, section) in stats.sections.iter().enumerate() {
+        let open: Vec<usize> = stats.days.iter().map(|day| day.open[i]).collect();
+        let (start, end) = (first.open[i], last.open[i]);
+        output.push_str(&format!(
+            "{:<width$}  {}  {} to {} ({:+})\n",
+            section.name,
+            sparkline(&open, glyphs),
+            start,
+            end,
+            end as i64 - start as i64
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+    use rusty_tasks::stats::{DayStats, SectionStats, Streak};
+
+    #[test]
+    fn test_format_stats() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let mut stats = Stats {
+            since: Some(date(1)),
+            days: vec![],
+            average_carry_over: None,
+            sections: vec![],
+            longest_streak: None,
+            oldest_open: None,
+        };
+        assert_eq!(
+            format_stats(&stats),
+            "days with notes   0 (since 2024-01-01)\n"
+        );
+
+        stats.days = vec![
+            DayStats {
+                date: date(1),
+                completed: 1,
+                open: vec![2, 0],
+            },
+            DayStats {
+                date: date(2),
+                completed: 2,
+                open: vec![1, 1],
+            },
+        ];
+        stats.average_carry_over = Some(1.5);
+        stats.sections = vec![
+            SectionStats {
+                name: "Daily".into(),
+                tasks: 4,
+                completed: 3,
+                completion_rate: Some(0.75),
+            },
+            SectionStats {
+                name: "Weekly".into(),
+                tasks: 0,
+                completed: 0,
+                completion_rate: None,
+            },
+        ];
+        stats.longest_streak = Some(Streak {
+            start: date(1),
+            end: date(2),
+            days: 2,
+        });
+        assert_eq!(
+            format_stats(&stats),
+            "\
+days with notes   2 (since 2024-01-01)
+completed         3 (1.5 per day)
+carried over      1.5 times on average before done
+longest streak    2 days (2024-01-01 to 2024-01-02)
+
+section     tasks  completed  rate
+Daily           4          3   75%
+Weekly          0          0     -
+"
+        );
+        assert_eq!(
+            format_days(&stats),
+            "\
+date         open  completed
+2024-01-01      2          1
+2024-01-02      2          2
+"
+        );
+        stats.longest_streak = Some(Streak {
+            start: date(2),
+            end: date(2),
+            days: 1,
+        });
+        assert!(
+            format_stats(&stats).contains("longest streak    1 day (2024-01-02 to 2024-01-02)\n")
+        );
+        stats.days[0].completed = 0;
+        assert_eq!(
+            format_charts(&stats, Glyphs::ASCII),
+            format!(
+                "\
+date        completed
+2024-01-01  0
+2024-01-02  {} 2
+
+open tasks  2024-01-01 to 2024-01-02
+Daily       #=  2 to 1 (-1)
+Weekly      _#  0 to 1 (+1)
+",
+                "#".repeat(BAR_WIDTH)
+            )
+        );
+    }
+}
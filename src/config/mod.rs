@@ -1,19 +1,65 @@
 extern crate serde;
 extern crate serde_json;
 
+use crate::file::DEFAULT_TITLE_FORMAT;
+use crate::todo::{formats_date, valid_file_pattern, TaskState, DEFAULT_FILE_PATTERN};
 use figment::providers::{Env, Format, Json, Serialized};
 use figment::Figment;
 use serde::{Deserialize, Serialize};
-use std::env::var;
-use std::fs::File;
-use std::io::Write;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::env::{var, VarError};
+use std::fmt;
+use std::fs::{create_dir_all, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Config {
     pub editor: String,
     pub sections: Vec<String>,
     pub notes_dir: String,
+    pub merge_missed: bool,
+    /// record the day unfinished tasks were first seen when carrying them over
+    pub track_age: bool,
+    /// number of days after which a task is considered stale
+    pub stale_after_days: Option<u32>,
+    pub stale_action: StaleAction,
+    /// number of days ahead each section covers, carry-over moves tasks with
+    /// a due date into the section whose horizon contains it
+    pub horizons: HashMap<String, u32>,
+    /// order the carried tasks of each section from highest to lowest
+    /// priority
+    pub sort_by_priority: bool,
+    /// meaning of status characters, `x` and `X` are done and any other
+    /// character is open unless mapped here
+    pub statuses: HashMap<char, TaskState>,
+    /// keep an index of the parsed notes in the notes directory
+    pub index: bool,
+    /// give carried tasks a block reference ID when they have none
+    pub assign_ids: bool,
+    /// path of a notes file relative to `notes_dir` as a strftime pattern,
+    /// e.g. `%Y/%m/%Y-%m-%d.md`
+    pub file_pattern: String,
+    /// title of a new notes file as a strftime pattern, also used to
+    /// recognize the title of existing files
+    pub title_format: String,
+    /// markdown file new notes files are created from, see the README for
+    /// its placeholders
+    pub template: Option<String>,
+}
+
+/// What carry-over does with stale tasks
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleAction {
+    /// leave them as they are, `show` still highlights them
+    #[default]
+    Highlight,
+    /// mark the open ones with `[!]`
+    Flag,
+    /// move them up to the previous section each time they get another
+    /// `stale_after_days` older
+    Escalate,
 }
 
 impl Default for Config {
@@ -22,52 +68,120 @@ impl Default for Config {
             editor: "nano".into(),
             sections: vec!["Daily".into(), "Weekly".into(), "Monthly".into()],
             notes_dir: "~/Notes".into(),
+            merge_missed: false,
+            track_age: false,
+            stale_after_days: None,
+            stale_action: StaleAction::default(),
+            horizons: HashMap::new(),
+            sort_by_priority: false,
+            statuses: HashMap::new(),
+            index: true,
+            assign_ids: false,
+            file_pattern: DEFAULT_FILE_PATTERN.into(),
+            title_format: DEFAULT_TITLE_FORMAT.into(),
+            template: None,
         }
     }
 }
 
 #[derive(Debug)]
 pub enum ConfigError {
-    IOError(&'static str),
-    ParseError(&'static str),
-    EnvError(&'static str),
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: Box<figment::Error>,
+    },
+    Serialize(serde_json::Error),
+    Env {
+        var: &'static str,
+        source: VarError,
+    },
+    FilePattern(String),
+    TitleFormat(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, .. } => {
+                write!(f, "could not write config {}", path.to_string_lossy())
+            }
+            ConfigError::Parse { path, .. } => {
+                write!(f, "could not load config {}", path.to_string_lossy())
+            }
+            ConfigError::Serialize(_) => write!(f, "could not serialize default config"),
+            ConfigError::Env { var, .. } => write!(f, "${} environment variable not set", var),
+            ConfigError::FilePattern(pattern) => {
+                write!(f, "file_pattern {:?} does not name files by date", pattern)
+            }
+            ConfigError::TitleFormat(format) => {
+                write!(
+                    f,
+                    "title_format {:?} does not contain the whole date",
+                    format
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source.as_ref()),
+            ConfigError::Serialize(source) => Some(source),
+            ConfigError::Env { source, .. } => Some(source),
+            ConfigError::FilePattern(_) | ConfigError::TitleFormat(_) => None,
+        }
+    }
 }
 
 impl Config {
-    pub fn load(cfg_file: &str) -> Result<Self, ConfigError> {
-        Figment::from(Serialized::defaults(Config::default()))
+    pub fn load(cfg_file: &Path) -> Result<Self, ConfigError> {
+        let cfg: Config = Figment::from(Serialized::defaults(Config::default()))
             .merge(Env::raw().only(&["EDITOR"]))
             .merge(Json::file(cfg_file))
             .extract()
-            .or(Err(ConfigError::IOError("Could not load config")))
+            .map_err(|e| ConfigError::Parse {
+                path: cfg_file.to_path_buf(),
+                source: Box::new(e),
+            })?;
+        if !valid_file_pattern(&cfg.file_pattern) {
+            return Err(ConfigError::FilePattern(cfg.file_pattern));
+        }
+        if !formats_date(&cfg.title_format) {
+            return Err(ConfigError::TitleFormat(cfg.title_format));
+        }
+        Ok(cfg)
     }
 
-    pub fn write_default(cfg_file: &str) -> Result<(), ConfigError> {
-        let buf = serde_json::to_string_pretty(&Self::default()).or_else(|_| {
-            Err(ConfigError::ParseError(
-                "could not serialize default config",
-            ))
-        })?;
+    pub fn write_default(cfg_file: &Path) -> Result<(), ConfigError> {
+        let buf = serde_json::to_string_pretty(&Self::default()).map_err(ConfigError::Serialize)?;
 
-        let mut f = File::create(cfg_file)
-            .or_else(|_| Err(ConfigError::IOError("Could not open config file")))?;
-        f.write_all(&buf.as_bytes()).or_else(|_| {
-            Err(ConfigError::IOError(
-                "could not write default config to file",
-            ))
-        })?;
+        let io_err = |source| ConfigError::Io {
+            path: cfg_file.to_path_buf(),
+            source,
+        };
+        if let Some(parent) = cfg_file.parent() {
+            create_dir_all(parent).map_err(io_err)?;
+        }
+        let mut f = File::create(cfg_file).map_err(io_err)?;
+        f.write_all(buf.as_bytes()).map_err(io_err)?;
 
         Ok(())
     }
 
     pub fn expected_locations() -> Result<Vec<PathBuf>, ConfigError> {
         let cfg_name = "rusty_task.json";
-        let home = var("HOME").or(Err(ConfigError::EnvError(
-            "$HOME environment variable not set",
-        )))?;
-        let pwd = var("PWD").or(Err(ConfigError::EnvError(
-            "$PWD environment variable not set",
-        )))?;
+        let home = var("HOME").map_err(|source| ConfigError::Env {
+            var: "HOME",
+            source,
+        })?;
+        let pwd = var("PWD").map_err(|source| ConfigError::Env { var: "PWD", source })?;
 
         let mut home_config_cfg = PathBuf::from(home.clone());
         home_config_cfg.push(".config");
@@ -82,3 +196,45 @@ impl Config {
         Ok(vec![home_config_cfg, home_cfg, pwd_cfg])
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn test_load() {
+        let dir = temp_dir().join(format!("rusty-tasks-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let load = |json: &str| {
+            let path = dir.join("config.json");
+            fs::write(&path, json).unwrap();
+            Config::load(&path)
+        };
+
+        let cfg = load(r#"{"file_pattern": "%Y/%m/%Y-%m-%d.md", "title_format": "%Y-%m-%d %a"}"#)
+            .unwrap();
+        assert_eq!(cfg.file_pattern, "%Y/%m/%Y-%m-%d.md");
+        assert_eq!(cfg.title_format, "%Y-%m-%d %a");
+        assert_eq!(cfg.sections, Config::default().sections);
+
+        assert!(matches!(
+            load(r#"{"file_pattern": "%Y-%m.md"}"#),
+            Err(ConfigError::FilePattern(_))
+        ));
+        assert!(matches!(
+            load(r#"{"file_pattern": "../%Y-%m-%d.md"}"#),
+            Err(ConfigError::FilePattern(_))
+        ));
+        assert!(matches!(
+            load(r#"{"title_format": "Tasks %Q"}"#),
+            Err(ConfigError::TitleFormat(_))
+        ));
+        assert!(matches!(
+            load(r#"{"sections": "Daily"}"#),
+            Err(ConfigError::Parse { .. })
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::ConfigError;
+use crate::file::FileNameParseError;
+use crate::todo::TaskError;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Crate level error covering every failure the CLI can report
+#[derive(Debug)]
+pub enum Error {
+    Config(ConfigError),
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    FileName(FileNameParseError),
+    Task {
+        path: PathBuf,
+        source: TaskError,
+    },
+    Render(io::Error),
+    Serialize(serde_json::Error),
+    SectionNotFound(String),
+    InvalidTask(String),
+    TaskNotFound(String),
+    AmbiguousTask {
+        pattern: String,
+        matches: Vec<String>,
+    },
+    Editor {
+        editor: String,
+        source: io::Error,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Attach the path of the file an io::Error occurred on
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        Error::Io {
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// Process exit code for this class of error (based on sysexits.h)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::FileName(_)
+            | Error::Task { .. }
+            | Error::SectionNotFound(_)
+            | Error::TaskNotFound(_)
+            | Error::AmbiguousTask { .. } => 65,
+            Error::InvalidTask(_) => 64,
+            Error::Editor { .. } => 69,
+            Error::Render(_) | Error::Serialize(_) => 70,
+            Error::Io { .. } => 74,
+            Error::Config(_) => 78,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(e) => write!(f, "{}", e),
+            Error::Io { path, .. } => write!(f, "could not access {}", path.to_string_lossy()),
+            Error::FileName(e) => write!(f, "{}", e),
+            Error::Task { path, source } => {
+                write!(f, "{}:{}", path.to_string_lossy(), source)
+            }
+            Error::Render(_) => write!(f, "could not render markdown"),
+            Error::Serialize(_) => write!(f, "could not serialize output"),
+            Error::SectionNotFound(section) => write!(f, "no section named {}", section),
+            Error::InvalidTask(text) => write!(f, "not a valid task: {:?}", text),
+            Error::TaskNotFound(task) => write!(f, "no task found for {}", task),
+            Error::AmbiguousTask { pattern, matches } => write!(
+                f,
+                "{:?} matches {} tasks: {}",
+                pattern,
+                matches.len(),
+                matches.join(", ")
+            ),
+            Error::Editor { editor, .. } => write!(f, "failed to launch editor {}", editor),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Config(e) => e.source(),
+            Error::FileName(e) => e.source(),
+            Error::Io { source, .. } | Error::Render(source) | Error::Editor { source, .. } => {
+                Some(source)
+            }
+            Error::Serialize(source) => Some(source),
+            Error::Task { .. }
+            | Error::SectionNotFound(_)
+            | Error::InvalidTask(_)
+            | Error::TaskNotFound(_)
+            | Error::AmbiguousTask { .. } => None,
+        }
+    }
+}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        Error::Config(e)
+    }
+}
+
+impl From<FileNameParseError> for Error {
+    fn from(e: FileNameParseError) -> Self {
+        Error::FileName(e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_error_messages() {
+        let err = Error::io(
+            "/notes/2024-01-01.md",
+            io::Error::new(io::ErrorKind::NotFound, "missing"),
+        );
+        assert_eq!(err.to_string(), "could not access /notes/2024-01-01.md");
+        assert_eq!(err.source().unwrap().to_string(), "missing");
+        assert_eq!(err.exit_code(), 74);
+
+        let err = Error::Task {
+            path: "/notes/2024-01-01.md".into(),
+            source: TaskError::ParsingError {
+                line: 4,
+                reason: "First child is not Paragraph",
+            },
+        };
+        assert_eq!(
+            err.to_string(),
+            "/notes/2024-01-01.md:4: First child is not Paragraph"
+        );
+        assert!(err.source().is_none());
+        assert_eq!(err.exit_code(), 65);
+
+        assert_eq!(Error::InvalidTask("".into()).exit_code(), 64);
+    }
+}
@@ -0,0 +1,172 @@
+use super::{append_text, extracted_list, set_task_status, task_items, CarryOptions};
+use crate::config::StaleAction;
+use crate::todo::{created_marker, Status, Task, TaskState};
+use chrono::NaiveDate;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+use indexmap::IndexMap;
+
+/// How many times a task of `age` days has gone stale, a task is stale once
+/// it is older than `after` days
+fn stale_level(age: i64, after: u32) -> i64 {
+    if age > 0 {
+        (age - 1) / i64::from(after.max(1))
+    } else {
+        0
+    }
+}
+
+/// whether the task `item` is open according to the statuses of `options`
+fn open<'a>(item: &'a AstNode<'a>, options: &CarryOptions) -> bool {
+    match item.data.borrow().value {
+        NodeValue::TaskItem(ch) => options.statuses.state(ch) == TaskState::Open,
+        _ => false,
+    }
+}
+
+/// Track the age of the unfinished tasks carried over into a file for
+/// `date`. Tasks without a first seen date are stamped with the date of the
+/// file they came from, given by `origin`. Stale tasks are then flagged or
+/// escalated to the previous section depending on `options`.
+pub(super) fn age_tasks<'a, F>(
+    arena: &'a Arena<AstNode<'a>>,
+    sections: &mut IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    date: &NaiveDate,
+    options: &CarryOptions,
+    origin: F,
+) where
+    F: Fn(&'a AstNode<'a>) -> NaiveDate,
+{
+    if !options.track_age {
+        return;
+    }
+
+    let mut escalate = Vec::new();
+    for (i, nodes) in sections.values().enumerate() {
+        let items = nodes
+            .iter()
+            .flatten()
+            .filter(|node| matches!(node.data.borrow().value, NodeValue::List(_)))
+            .flat_map(|list| task_items(list));
+
+        for item in items {
+            let from = origin(item);
+            let created = match Task::try_from(item).ok().and_then(|t| t.dates.created) {
+                Some(created) => created,
+                None => {
+                    append_text(arena, item, &created_marker(&from));
+                    from
+                }
+            };
+            let Some(after) = options.stale_after_days else {
+                continue;
+            };
+
+            let age = (*date - created).num_days();
+            match options.stale_action {
+                StaleAction::Highlight => {}
+                // in progress and deferred tasks keep their status
+                StaleAction::Flag if stale_level(age, after) > 0 && open(item, options) => {
+                    set_task_status(item, &Status::Todo('!'))
+                }
+                StaleAction::Flag => {}
+                StaleAction::Escalate => {
+                    let before = stale_level((from - created).num_days(), after);
+                    if i > 0 && stale_level(age, after) > before {
+                        escalate.push((i - 1, item));
+                    }
+                }
+            }
+        }
+    }
+
+    for (i, item) in escalate {
+        let (_, nodes) = sections
+            .get_index_mut(i)
+            .expect("escalating to an existing section");
+        extracted_list(arena, nodes).append(item);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::carry_over_with_report;
+    use crate::todo::StatusMap;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_age_tasks() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-10
+
+## Daily
+
+- [ ] new task
+- [ ] old task ➕ 2024-01-01
+
+## Weekly
+
+- [ ] weekly task ➕ 2024-01-03
+- [ ] **bold task**
+- [/] started task ➕ 2024-01-02
+";
+        let mut options = CarryOptions::new(&["Daily".to_string(), "Weekly".to_string()]);
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert!(content.contains("- [ ] new task\n"));
+
+        options.track_age = true;
+        options.stale_after_days = Some(7);
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-11
+
+## Daily
+
+- [ ] new task ➕ 2024-01-10
+- [ ] old task ➕ 2024-01-01
+
+## Weekly
+
+- [ ] weekly task ➕ 2024-01-03
+- [ ] **bold task** ➕ 2024-01-10
+- [/] started task ➕ 2024-01-02
+"
+        );
+
+        options.stale_action = StaleAction::Flag;
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert!(content.contains("- [!] old task ➕ 2024-01-01\n"));
+        assert!(content.contains("- [!] weekly task ➕ 2024-01-03\n"));
+        assert!(content.contains("- [ ] new task ➕ 2024-01-10\n"));
+        // a stale task in progress keeps its status
+        options.statuses = StatusMap::new(HashMap::from([('/', TaskState::InProgress)]));
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert!(content.contains("- [/] started task ➕ 2024-01-02\n"));
+        assert!(content.contains("- [!] weekly task ➕ 2024-01-03\n"));
+
+        // the weekly task turns 8 days old and is moved up, the old task
+        // stays in the first section
+        options.stale_action = StaleAction::Escalate;
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert!(content.contains("- [ ] old task ➕ 2024-01-01\n- [ ] weekly task ➕ 2024-01-03\n"));
+
+        // only once each time it gets another 7 days older
+        let (content, _) =
+            carry_over_with_report(contents, &date(11), &date(12), &options).unwrap();
+        assert!(content.contains("## Weekly\n\n- [ ] weekly task"));
+
+        assert_eq!(stale_level(7, 7), 0);
+        assert_eq!(stale_level(8, 7), 1);
+        assert_eq!(stale_level(15, 7), 2);
+        assert_eq!(stale_level(-3, 7), 0);
+    }
+}
@@ -0,0 +1,146 @@
+use super::{append_text, subtask_items, task_items, CarryOptions};
+use crate::todo::{id_marker, Task};
+use chrono::NaiveDate;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+
+/// Number of characters in an assigned ID
+const ID_LEN: usize = 4;
+
+/// 64 bit FNV-1a hash of `bytes`, unlike `DefaultHasher` it does not change
+/// between Rust releases
+fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Short lowercase alphanumeric ID derived from `seed`, different for each
+/// `attempt`
+fn generate_id(seed: &str, attempt: u32) -> String {
+    let mut hash = stable_hash(format!("{} {}", seed, attempt).as_bytes());
+    let mut id = String::with_capacity(ID_LEN);
+    for _ in 0..ID_LEN {
+        id.push(char::from_digit((hash % 36) as u32, 36).unwrap_or('0'));
+        hash /= 36;
+    }
+    id
+}
+
+/// Task `items` along with all of their subtask items
+fn all_task_items<'a>(
+    items: impl Iterator<Item = &'a AstNode<'a>>,
+    out: &mut Vec<&'a AstNode<'a>>,
+) {
+    for item in items {
+        out.push(item);
+        all_task_items(subtask_items(item), out);
+    }
+}
+
+/// Give every carried task and subtask without an ID a new one, when enabled
+/// in `options`. New IDs are unique within the new file and differ from the
+/// IDs already used in other notes files, `options.known_ids`.
+pub(super) fn assign_ids<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    sections: &IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    date: &NaiveDate,
+    options: &CarryOptions,
+) {
+    if !options.assign_ids {
+        return;
+    }
+    let mut items = Vec::new();
+    for list in sections
+        .values()
+        .flatten()
+        .flatten()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::List(_)))
+    {
+        all_task_items(task_items(list), &mut items);
+    }
+
+    let tasks: Vec<_> = items
+        .into_iter()
+        .filter_map(|item| Task::try_from(item).ok().map(|task| (item, task)))
+        .collect();
+    let mut taken: HashSet<String> = tasks.iter().filter_map(|(_, t)| t.id.clone()).collect();
+    taken.extend(options.known_ids.iter().cloned());
+    for (item, task) in tasks {
+        if task.id.is_some() {
+            continue;
+        }
+        let seed = format!("{} {}", date, task.text);
+        let id = (0..)
+            .map(|attempt| generate_id(&seed, attempt))
+            .find(|id| !taken.contains(id))
+            .expect("an unused id");
+        append_text(arena, item, &id_marker(&id));
+        taken.insert(id);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::{carry_over_with_report, find_tasks, parse_todo_file};
+
+    #[test]
+    fn test_assign_ids() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] write docs ^docs
+- [ ] review
+  - [ ] subtask
+- [x] done
+";
+        let mut options = CarryOptions::new(&["Daily".to_string()]);
+        options.assign_ids = true;
+        options.track_age = true;
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+
+        let arena = Arena::new();
+        let root = parse_todo_file(&content, &arena);
+        let tasks = find_tasks(root, &options.sections);
+        let ids: Vec<String> = tasks
+            .iter()
+            .filter_map(|t| Task::try_from(t.node).ok().and_then(|t| t.id))
+            .collect();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], "docs");
+        assert!(ids[1..].iter().all(|id| id.len() == ID_LEN));
+        assert_ne!(ids[1], ids[2]);
+        // dates are added before the block reference
+        assert!(content.contains("- [ ] write docs ➕ 2024-01-02 ^docs\n"));
+
+        // IDs are kept from then on
+        let (again, _) = carry_over_with_report(&content, &date, &date, &options).unwrap();
+        assert_eq!(again, content);
+
+        assert_ne!(generate_id("seed", 0), generate_id("seed", 1));
+        // IDs only depend on their seed
+        assert_eq!(generate_id("seed", 0), "4egb");
+
+        // IDs used in other files are not given out again
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        let used = |content: &str| -> Vec<String> {
+            let arena = Arena::new();
+            let root = parse_todo_file(content, &arena);
+            find_tasks(root, &options.sections)
+                .iter()
+                .filter_map(|t| Task::try_from(t.node).ok().and_then(|t| t.id))
+                .collect()
+        };
+        let ids = used(&content);
+        options.known_ids = ids[1..].iter().cloned().collect();
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        let new_ids = used(&content);
+        assert!(new_ids[1..].iter().all(|id| !ids.contains(id)));
+    }
+}
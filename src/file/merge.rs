@@ -0,0 +1,225 @@
+use super::{
+    age, extract_sections, extracted_list, find_tasks, ids, parse_todo_file, promote, recur,
+    render_new_doc, task_items, CarryOptions, CarryReport,
+};
+use crate::error::Result;
+use crate::todo::Task;
+use chrono::NaiveDate;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+const CARRIED_FROM_FMT: &str = "%Y-%m-%d";
+
+/// Marker left under the title of a merged notes file
+static CARRIED_FROM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<!-- carried from (\d+-\d+-\d+) -->").expect("carried from regex is not parsable")
+});
+
+/// Date of the newest file merged into a notes file by
+/// [`carry_over_merged`], read from the marker it leaves under the title
+pub fn carried_from(contents: &str) -> Option<NaiveDate> {
+    let date = CARRIED_FROM_RE.captures(contents)?.get(1)?;
+    NaiveDate::parse_from_str(date.as_str(), CARRIED_FROM_FMT).ok()
+}
+
+/// text of a task along with its ID, either identifies it across files
+fn task_keys(task: &Task) -> Vec<String> {
+    let mut keys = vec![task.text.trim().to_string()];
+    keys.extend(task.id.as_ref().map(|id| format!("^{}", id)));
+    keys
+}
+
+/// keys of the top level tasks in `sections`, in any state
+fn task_texts<'a>(root: &'a AstNode<'a>, sections: &[String]) -> Vec<String> {
+    find_tasks(root, sections)
+        .into_iter()
+        .filter(|task_ref| task_ref.path.len() == 1)
+        .filter_map(|task_ref| Task::try_from(task_ref.node).ok())
+        .flat_map(|task| task_keys(&task))
+        .collect()
+}
+
+/// Generate the contents of a new notes file for `date` from several
+/// previous ones, given as `(date, contents)` ordered newest first.
+///
+/// Unfinished tasks from the newest file are carried over as with
+/// [`carry_over`](super::carry_over). Unfinished tasks from older files are
+/// added to the same section unless a task with identical text or the same
+/// ID appears in a newer file, whatever its status. The date of the newest
+/// file is recorded in the new file so the next merge can start from it (see
+/// [`carried_from`]).
+pub fn carry_over_merged(
+    sources: &[(NaiveDate, String)],
+    date: &NaiveDate,
+    options: &CarryOptions,
+) -> Result<(String, CarryReport)> {
+    let sections = &options.sections;
+    let arena = Arena::new();
+    let mut roots = sources
+        .iter()
+        .map(|(from, contents)| (from, parse_todo_file(contents, &arena)));
+    let Some((newest_date, newest)) = roots.next() else {
+        return Ok((String::new(), CarryReport::default()));
+    };
+
+    let statuses = &options.statuses;
+    let recurring = recur::renew_recurring(&arena, newest, newest_date, sections, statuses);
+    let mut report = CarryReport::new(newest, sections, statuses);
+    report.add_recurring(&recurring);
+    let mut seen: HashSet<String> = task_texts(newest, sections).into_iter().collect();
+    let mut merged = extract_sections(newest, sections, statuses);
+    // date of the file each merged task came from, for tracking its age
+    let mut origins: HashMap<*const AstNode, NaiveDate> = HashMap::new();
+
+    for (from, root) in roots {
+        let texts = task_texts(root, sections);
+        for (i, (section, nodes)) in extract_sections(root, sections, statuses)
+            .into_iter()
+            .enumerate()
+        {
+            let items: Vec<&AstNode> = nodes
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|node| matches!(node.data.borrow().value, NodeValue::List(_)))
+                .flat_map(task_items)
+                .collect();
+
+            for item in items {
+                let Ok(task) = Task::try_from(item) else {
+                    continue;
+                };
+                let keys = task_keys(&task);
+                if keys.iter().any(|key| seen.contains(key)) {
+                    continue;
+                }
+                seen.extend(keys);
+
+                let target = merged
+                    .get_mut(&section)
+                    .expect("sections are the same for every file");
+                extracted_list(&arena, target).append(item);
+                origins.insert(item, *from);
+                report.sections[i].merged += 1;
+            }
+        }
+        seen.extend(texts);
+    }
+    age::age_tasks(&arena, &mut merged, date, options, |item| {
+        origins
+            .get(&(item as *const AstNode))
+            .copied()
+            .unwrap_or(*newest_date)
+    });
+    promote::promote_tasks(&arena, &mut merged, date, options);
+    ids::assign_ids(&arena, &merged, date, options);
+
+    let marker = format!(
+        "<!-- carried from {} -->\n",
+        sources[0].0.format(CARRIED_FROM_FMT)
+    );
+    let content = render_new_doc(
+        &arena,
+        date,
+        Some((newest_date, newest)),
+        merged,
+        options,
+        Some(&marker),
+    )?;
+
+    Ok((content, report))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_carry_over_merged() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let sources = vec![
+            (
+                date(11),
+                "\
+# Today's tasks 2024-01-11
+<!-- carried from 2024-01-09 -->
+
+## Daily
+
+- [ ] shared task
+- [x] done on desktop
+- [ ] desktop task
+- [x] renamed on desktop ^r1
+
+## Weekly
+"
+                .to_string(),
+            ),
+            (
+                date(10),
+                "\
+# Today's tasks 2024-01-10
+
+## Daily
+
+- [ ] shared task
+- [ ] done on desktop
+- [ ] laptop task
+  - [ ] laptop subtask
+  - [x] done subtask
+- [x] done on laptop
+- [ ] renamed ^r1
+
+## Weekly
+
+- [ ] weekly laptop task
+"
+                .to_string(),
+            ),
+            (
+                date(9),
+                "\
+# Today's tasks 2024-01-09
+
+## Daily
+
+- [ ] laptop task
+- [ ] old task
+"
+                .to_string(),
+            ),
+        ];
+        let options = CarryOptions::new(&["Daily".to_string(), "Weekly".to_string()]);
+
+        let (content, report) = carry_over_merged(&sources, &date(17), &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-17
+
+<!-- carried from 2024-01-11 -->
+
+## Daily
+
+- [ ] shared task
+- [ ] desktop task
+- [ ] laptop task
+  - [ ] laptop subtask
+- [ ] old task
+
+## Weekly
+
+- [ ] weekly laptop task
+"
+        );
+        assert_eq!(carried_from(&content), Some(date(11)));
+        assert_eq!(carried_from(&sources[2].1), None);
+
+        assert_eq!(report.sections[0].carried, 2);
+        assert_eq!(report.sections[0].dropped, 2);
+        assert_eq!(report.sections[0].merged, 2);
+        assert_eq!(report.sections[1].merged, 1);
+    }
+}
@@ -1,87 +1,384 @@
-use crate::todo::{File as TodoFile, Status as TaskStatus};
-use crate::NaiveDate;
-use crate::TaskGroup;
-use chrono::Datelike;
-use comrak::nodes::{Ast, AstNode, LineColumn, NodeHeading, NodeValue};
+mod age;
+mod ids;
+mod merge;
+mod promote;
+mod recur;
+mod report;
+mod review;
+mod template;
+
+pub use merge::{carried_from, carry_over_merged};
+pub use report::{CarryReport, SectionReport};
+pub use review::{create_review, review_filepath, ReviewPeriod};
+
+use crate::config::{Config, StaleAction};
+use crate::error::{Error, Result};
+use crate::todo::{
+    format_date, id_offset, unescape_tags, File as TodoFile, Status as TaskStatus, StatusMap, Task,
+    TaskGroup, DEFAULT_FILE_PATTERN,
+};
+use chrono::NaiveDate;
+use comrak::nodes::{
+    Ast, AstNode, LineColumn, ListDelimType, ListType, NodeHeading, NodeList, NodeValue,
+};
 use comrak::{
     format_commonmark, parse_document, Arena, ComrakOptions, ExtensionOptions, ParseOptions,
 };
 use indexmap::IndexMap;
-use regex::Regex;
-use std::collections::HashMap;
-use std::fs::{read, File};
-use std::io::Write;
-use std::path::PathBuf;
-use std::str;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{create_dir_all, read_dir, read_to_string, File};
+use std::io::{self, BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug)]
 pub enum FileNameParseError {
-    TypeConversionError(&'static str),
-    ParseError(chrono::ParseError),
+    TypeConversionError(PathBuf),
+    ParseError {
+        path: PathBuf,
+        source: chrono::ParseError,
+    },
+}
+
+impl fmt::Display for FileNameParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileNameParseError::TypeConversionError(path) => write!(
+                f,
+                "could not get filename from path: {}",
+                path.to_string_lossy()
+            ),
+            FileNameParseError::ParseError { path, .. } => {
+                write!(f, "could not get date from {}", path.to_string_lossy())
+            }
+        }
+    }
+}
+
+impl std::error::Error for FileNameParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FileNameParseError::TypeConversionError(_) => None,
+            FileNameParseError::ParseError { source, .. } => Some(source),
+        }
+    }
 }
 
-pub fn get_filepath(data_dir: &PathBuf, date: &NaiveDate) -> PathBuf {
-    let file_name = format!("{}-{:02}-{:02}.md", date.year(), date.month(), date.day());
-    let mut file_path = data_dir.clone();
-    file_path.push(file_name);
-    file_path
+/// Path of the notes file for `date`, named after `pattern` (a valid
+/// `Config::file_pattern`)
+pub fn get_filepath(data_dir: &Path, date: &NaiveDate, pattern: &str) -> PathBuf {
+    data_dir.join(date.format(pattern).to_string())
 }
 
-/// generate strings from TaskGroups and date
-pub fn generate_file_content(data: &Vec<TaskGroup>, date: &NaiveDate) -> String {
+/// list the files `depth` directories down in the notes directory, 1 for the
+/// files directly in it, skipping hidden files and directories such as the
+/// index or `.obsidian`
+pub fn list_files(data_dir: &Path, depth: usize) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in read_dir(data_dir)
+        .map_err(|e| Error::io(data_dir, e))?
+        .filter_map(|f| f.ok())
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+    {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                if depth > 1 {
+                    files.extend(list_files(&path, depth - 1)?);
+                }
+            }
+            Ok(_) if depth == 1 => files.push(path),
+            Ok(_) => continue,
+            Err(e) => log::warn!("skipping {}: {}", path.display(), e),
+        }
+    }
+    Ok(files)
+}
+
+/// generate strings from TaskGroups and date, titled after `title_format`
+pub fn generate_file_content(data: &[TaskGroup], date: &NaiveDate, title_format: &str) -> String {
     // TODO: This should be a type and then I can implement it with From<>
     let mut content = format!(
-        "# Today's tasks {}-{:02}-{:02}\n",
-        date.year(),
-        date.month(),
-        date.day()
+        "# {}\n",
+        format_date(date, title_format).unwrap_or_default()
     );
     data.iter()
-        .for_each(|task_group| content.push_str(format!("\n{}", task_group.to_string()).as_str()));
+        .for_each(|task_group| content.push_str(format!("\n{}", task_group).as_str()));
 
     content
 }
 
-pub fn write_file(path: &PathBuf, content: &String) {
-    let mut new_file = File::create(&path).expect("Could not open today's file: {today_file_path}");
-    write!(new_file, "{}", content).expect("Could not write to file: {today_file_path}");
+/// Write `content` to `path`, creating its directory if needed
+pub fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|e| Error::io(parent, e))?;
+    }
+    let mut new_file = File::create(path).map_err(|e| Error::io(path, e))?;
+    write!(new_file, "{}", content).map_err(|e| Error::io(path, e))
 }
 
 /// Load in text file as String
-pub fn load_file(file: &TodoFile) -> String {
-    // TODO: This could be a TryFrom<>
-    let contents_utf8 = read(file.file.clone())
-        .expect(format!("Could not read file {}", file.file.to_string_lossy()).as_str());
-    str::from_utf8(&contents_utf8)
-        .expect(
-            format!(
-                "failed to convert contents of file to string: {}",
-                file.file.to_string_lossy()
-            )
-            .as_str(),
-        )
-        .to_string()
+pub fn load_file(file: &TodoFile) -> Result<String> {
+    read_to_string(&file.file).map_err(|e| Error::io(&file.file, e))
 }
 
-/// Parse contents of markdown file with Comrak ( relaxed tasklist matching is enabled)
-pub fn parse_todo_file<'a>(contents: &String, arena: &'a Arena<AstNode<'a>>) -> &'a AstNode<'a> {
+/// Comrak options used for parsing and rendering notes (tasklists enabled)
+pub fn comrak_options() -> ComrakOptions {
     let mut extension_options = ExtensionOptions::default();
     extension_options.tasklist = true;
 
     let mut parse_options = ParseOptions::default();
     parse_options.relaxed_tasklist_matching = true;
 
-    let options = &ComrakOptions {
+    ComrakOptions {
         extension: extension_options,
         parse: parse_options,
         ..ComrakOptions::default()
-    };
-    parse_document(arena, contents, options)
+    }
+}
+
+/// Parse contents of markdown file with Comrak ( relaxed tasklist matching is enabled)
+pub fn parse_todo_file<'a>(contents: &str, arena: &'a Arena<AstNode<'a>>) -> &'a AstNode<'a> {
+    parse_document(arena, contents, &comrak_options())
+}
+
+/// Render a document tree back to markdown, keeping tags as they were
+/// written
+pub fn render_doc<'a>(root: &'a AstNode<'a>) -> Result<String> {
+    let mut output = BufWriter::new(Vec::new());
+    format_commonmark(root, &comrak_options(), &mut output).map_err(Error::Render)?;
+    let bytes = output
+        .into_inner()
+        .map_err(|e| Error::Render(e.into_error()))?;
+    String::from_utf8(bytes)
+        .map(|markdown| unescape_tags(&markdown).into_owned())
+        .map_err(|e| Error::Render(io::Error::new(io::ErrorKind::InvalidData, e)))
+}
+
+/// Title of a new notes file when none is configured
+pub const DEFAULT_TITLE_FORMAT: &str = "Today's tasks %Y-%m-%d";
+
+/// Settings for carrying tasks over into a new notes file
+#[derive(Debug, Clone)]
+pub struct CarryOptions {
+    /// sections to carry over, most urgent first
+    pub sections: Vec<String>,
+    /// stamp carried tasks with the day they were first seen
+    pub track_age: bool,
+    /// age in days after which a stamped task is stale
+    pub stale_after_days: Option<u32>,
+    pub stale_action: StaleAction,
+    /// number of days ahead each section covers, tasks are moved between
+    /// these sections by their due date
+    pub horizons: HashMap<String, u32>,
+    /// order the tasks of each section by priority, highest first
+    pub sort_by_priority: bool,
+    /// meaning of status characters, finished tasks are not carried over
+    pub statuses: StatusMap,
+    /// give carried tasks without an ID one
+    pub assign_ids: bool,
+    /// strftime pattern of the title of the new file, also recognizing the
+    /// title of the file carried from
+    pub title_format: String,
+    /// contents of the template the new file is made from
+    pub template: Option<String>,
+    /// strftime pattern of the path of notes files, for links to them
+    pub file_pattern: String,
+    /// IDs of tasks in other notes files, assigned IDs avoid them
+    pub known_ids: HashSet<String>,
+}
+
+impl Default for CarryOptions {
+    fn default() -> Self {
+        CarryOptions {
+            sections: Vec::new(),
+            track_age: false,
+            stale_after_days: None,
+            stale_action: StaleAction::default(),
+            horizons: HashMap::new(),
+            sort_by_priority: false,
+            statuses: StatusMap::default(),
+            assign_ids: false,
+            title_format: DEFAULT_TITLE_FORMAT.into(),
+            template: None,
+            file_pattern: DEFAULT_FILE_PATTERN.into(),
+            known_ids: HashSet::new(),
+        }
+    }
+}
+
+impl CarryOptions {
+    /// carry over `sections` without tracking the age of tasks
+    pub fn new(sections: &[String]) -> Self {
+        CarryOptions {
+            sections: sections.to_vec(),
+            ..CarryOptions::default()
+        }
+    }
 }
 
-pub fn extract_secitons<'a>(
+impl From<&Config> for CarryOptions {
+    fn from(cfg: &Config) -> Self {
+        CarryOptions {
+            sections: cfg.sections.clone(),
+            track_age: cfg.track_age,
+            stale_after_days: cfg.stale_after_days,
+            stale_action: cfg.stale_action,
+            horizons: cfg.horizons.clone(),
+            sort_by_priority: cfg.sort_by_priority,
+            statuses: StatusMap::from(&cfg.statuses),
+            assign_ids: cfg.assign_ids,
+            title_format: cfg.title_format.clone(),
+            // the template is read by whoever loads the config
+            template: None,
+            file_pattern: cfg.file_pattern.clone(),
+            // as are the IDs of the other notes
+            known_ids: HashSet::new(),
+        }
+    }
+}
+
+/// Generate the contents of a new notes file for `date` from the contents of
+/// a previous one, carrying over unfinished tasks in `sections`
+pub fn carry_over(contents: &str, date: &NaiveDate, sections: &[String]) -> Result<String> {
+    // age is not tracked so the date of the previous file does not matter
+    carry_over_with_report(contents, date, date, &CarryOptions::new(sections))
+        .map(|(content, _)| content)
+}
+
+/// Same as [`carry_over`] with more `options`, also reporting how many tasks
+/// were carried. `from` is the date of the file being carried from.
+pub fn carry_over_with_report(
+    contents: &str,
+    from: &NaiveDate,
+    date: &NaiveDate,
+    options: &CarryOptions,
+) -> Result<(String, CarryReport)> {
+    let arena = Arena::new();
+    let root = parse_todo_file(contents, &arena);
+    let recurring =
+        recur::renew_recurring(&arena, root, from, &options.sections, &options.statuses);
+    let mut report = CarryReport::new(root, &options.sections, &options.statuses);
+    report.add_recurring(&recurring);
+
+    let mut sect = extract_sections(root, &options.sections, &options.statuses);
+    age::age_tasks(&arena, &mut sect, date, options, |_| *from);
+    promote::promote_tasks(&arena, &mut sect, date, options);
+    ids::assign_ids(&arena, &sect, date, options);
+    let content = render_new_doc(&arena, date, Some((from, root)), sect, options, None)?;
+
+    Ok((content, report))
+}
+
+/// Contents of a notes file for `date` with the configured sections left
+/// empty, for when there are no previous notes to carry over from
+pub fn empty_file(date: &NaiveDate, options: &CarryOptions) -> Result<String> {
+    let arena = Arena::new();
+    let sections = options
+        .sections
+        .iter()
+        .map(|section| (section.clone(), None))
+        .collect();
+    render_new_doc(&arena, date, None, sections, options, None)
+}
+
+/// Title heading of the carried document `root` with its date changed to
+/// `date`, if `title_format` recognizes it. Anything after the date in the
+/// title is kept.
+fn carried_title<'a>(
+    root: &'a AstNode<'a>,
+    date: &NaiveDate,
+    title_format: &str,
+) -> Option<&'a AstNode<'a>> {
+    let title = root.children().find(|node| {
+        matches!(
+            node.data.borrow().value,
+            NodeValue::Heading(NodeHeading { level: 1, .. })
+        )
+    })?;
+    {
+        let text_node = title.first_child()?;
+        let mut data = text_node.data.borrow_mut();
+        let NodeValue::Text(text) = &mut data.value else {
+            return None;
+        };
+        let (_, rest) = NaiveDate::parse_and_remainder(text, title_format).ok()?;
+        let new_text = format_date(date, title_format)? + rest;
+        *text = new_text;
+    }
+    if let NodeValue::Heading(heading) = &mut title.data.borrow_mut().value {
+        heading.setext = false;
+    }
+    title.detach();
+    Some(title)
+}
+
+/// Render a new notes file for `date` out of the extracted `sections`, from
+/// the template in `options` if there is one. `from` is the date and
+/// document of the file carried from, its title gets the new date when it is
+/// recognized. `marker` goes right below the title.
+fn render_new_doc<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    date: &NaiveDate,
+    from: Option<(&NaiveDate, &'a AstNode<'a>)>,
+    sections: IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    options: &CarryOptions,
+    marker: Option<&str>,
+) -> Result<String> {
+    let carried = from.and_then(|(_, root)| carried_title(root, date, &options.title_format));
+    let from = from.map(|(from, _)| from);
+
+    if let Some(template) = &options.template {
+        let title = match carried {
+            Some(heading) => {
+                let doc = arena.alloc(AstNode::new(
+                    Ast::new(NodeValue::Document, LineColumn { line: 0, column: 0 }).into(),
+                ));
+                doc.append(heading);
+                render_doc(doc)?.trim_start_matches('#').trim().to_string()
+            }
+            None => format_date(date, &options.title_format).unwrap_or_default(),
+        };
+        let content =
+            template::fill_template(arena, template, &title, date, from, sections, options)?;
+        let Some(marker) = marker else {
+            return Ok(content);
+        };
+        // below the first title of the template, or at the very top
+        let mut lines: Vec<&str> = content.lines().collect();
+        let title = lines.iter().position(|line| line.starts_with("# "));
+        let at = title.map_or(0, |i| i + 1);
+        let marker = match title {
+            Some(_) => format!("\n{}", marker.trim_end()),
+            None => format!("{}\n", marker.trim_end()),
+        };
+        lines.insert(at, &marker);
+        return Ok(lines.join("\n") + "\n");
+    }
+
+    let title = format_date(date, &options.title_format).unwrap_or_default();
+    let doc = create_new_doc(arena, &title, sections, options.sort_by_priority);
+    if let (Some(title), Some(heading)) = (doc.first_child(), carried) {
+        title.insert_before(heading);
+        title.detach();
+    }
+    if let (Some(title), Some(marker)) = (
+        doc.first_child(),
+        marker.and_then(|marker| parse_todo_file(marker, arena).first_child()),
+    ) {
+        title.insert_after(marker);
+    }
+    render_doc(doc)
+}
+
+/// Collect the TaskGroups in `sections` without the tasks that are finished
+/// according to `statuses`
+pub fn extract_task_groups<'a>(
     root: &'a AstNode<'a>,
-    sections: &Vec<String>,
+    sections: &[String],
+    statuses: &StatusMap,
 ) -> HashMap<String, TaskGroup> {
     let mut groups: HashMap<String, TaskGroup> = HashMap::new();
     for node in root.reverse_children() {
@@ -107,11 +404,9 @@ pub fn extract_secitons<'a>(
 
             if sections.iter().any(|section| section.eq(title)) {
                 if let Ok(mut group) = TaskGroup::try_from(node) {
-                    group.tasks = group
+                    group
                         .tasks
-                        .into_iter()
-                        .filter(|task| !matches!(task.status, TaskStatus::Done(_)))
-                        .collect();
+                        .retain(|task| !statuses.status_state(&task.status).is_finished());
                     groups.insert(title.to_string(), group);
                 }
             }
@@ -121,9 +416,7 @@ pub fn extract_secitons<'a>(
 }
 
 fn remove_heading<'a>(node: &'a AstNode<'a>, level: u8) {
-    let mut following = node.following_siblings();
-    let _ = following.next().unwrap();
-    for sib in following {
+    for sib in node.following_siblings().skip(1) {
         let node_ref = sib.data.borrow();
         if let NodeValue::Heading(heading) = node_ref.value {
             if heading.level == level {
@@ -136,25 +429,21 @@ fn remove_heading<'a>(node: &'a AstNode<'a>, level: u8) {
     node.detach();
 }
 
-/// recursively removes nodes from List
-fn remove_task_nodes<'a>(root: &'a AstNode<'a>) {
+/// recursively removes finished task nodes from List
+fn remove_task_nodes<'a>(root: &'a AstNode<'a>, statuses: &StatusMap) {
     for node in root.children() {
         for child_node in node.children() {
-            remove_task_nodes(child_node)
+            remove_task_nodes(child_node, statuses)
         }
         match node.data.borrow().value {
-            NodeValue::TaskItem(Some(status)) if status == 'x' || status == 'X' => node.detach(),
+            NodeValue::TaskItem(ch) if statuses.state(ch).is_finished() => node.detach(),
             _ => continue,
         }
     }
 }
 
-fn create_title<'a>(arena: &'a Arena<AstNode<'a>>, date: &str) -> &'a AstNode<'a> {
-    let mut text = String::new();
-    text.push_str("Today's tasks ");
-    text.push_str(date);
-
-    create_heading(arena, 1, &text)
+fn create_title<'a>(arena: &'a Arena<AstNode<'a>>, title: &str) -> &'a AstNode<'a> {
+    create_heading(arena, 1, title)
 }
 
 fn create_heading<'a>(arena: &'a Arena<AstNode<'a>>, level: u8, text: &str) -> &'a AstNode<'a> {
@@ -181,35 +470,59 @@ fn create_heading<'a>(arena: &'a Arena<AstNode<'a>>, level: u8, text: &str) -> &
     heading_node
 }
 
+/// Build a notes document titled `title` out of the extracted `sections`.
+/// With `sort_by_priority` the tasks of each section are ordered from highest
+/// to lowest priority, keeping their order otherwise.
 pub fn create_new_doc<'a>(
     arena: &'a Arena<AstNode<'a>>,
-    new_date: &str,
+    title: &str,
     sections: IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    sort_by_priority: bool,
 ) -> &'a AstNode<'a> {
     let doc = arena.alloc(AstNode::new(
         Ast::new(NodeValue::Document, LineColumn { line: 0, column: 0 }).into(),
     ));
-    let title = create_title(&arena, new_date);
-    doc.append(title);
+    doc.append(create_title(arena, title));
 
     for (section, value) in sections.iter() {
-        let heading = create_heading(arena, 2, &section);
+        let heading = create_heading(arena, 2, section);
         doc.append(heading);
-        match value {
-            Some(nodes) => {
-                for node in nodes.iter() {
-                    doc.append(node);
+        if let Some(nodes) = value {
+            for node in nodes.iter() {
+                if sort_by_priority && matches!(node.data.borrow().value, NodeValue::List(_)) {
+                    sort_list(node);
                 }
+                doc.append(node);
             }
-            _ => (),
         }
     }
     doc
 }
 
+/// Stable sort the items of a list by the priority of their task, plain list
+/// items count as normal priority
+fn sort_list<'a>(list: &'a AstNode<'a>) {
+    let mut items: Vec<_> = list
+        .children()
+        .map(|item| {
+            let priority = Task::try_from(item)
+                .map(|task| task.priority)
+                .unwrap_or_default();
+            (Reverse(priority), item)
+        })
+        .collect();
+    items.sort_by_key(|(priority, _)| *priority);
+    for (_, item) in items {
+        list.append(item);
+    }
+}
+
+/// Detach the contents of `sections` from the document, without the tasks
+/// that are finished according to `statuses`
 pub fn extract_sections<'a>(
     root: &'a AstNode<'a>,
-    sections: &Vec<String>,
+    sections: &[String],
+    statuses: &StatusMap,
 ) -> IndexMap<String, Option<Vec<&'a AstNode<'a>>>> {
     let mut section_map: IndexMap<String, Option<Vec<&'a AstNode<'a>>>> = IndexMap::new();
     sections.iter().for_each(|section| {
@@ -230,11 +543,9 @@ pub fn extract_sections<'a>(
                 if let NodeValue::Text(text) = &mut heading_content_ref.value {
                     if sections.contains(text) {
                         let mut content = Vec::new();
-                        let mut following = node.following_siblings();
-                        let _ = following.next().unwrap();
 
-                        for sib in following {
-                            remove_task_nodes(sib);
+                        for sib in node.following_siblings().skip(1) {
+                            remove_task_nodes(sib, statuses);
                             let node_ref = sib.data.borrow();
                             if let NodeValue::Heading(inner_heading) = node_ref.value {
                                 if heading.level == inner_heading.level {
@@ -256,41 +567,379 @@ pub fn extract_sections<'a>(
     section_map
 }
 
-pub fn process_doc_tree<'a>(root: &'a AstNode<'a>, new_date: &str, sections: &Vec<String>) {
-    for node in root.reverse_children() {
-        let node_ref = node.data.borrow();
-        match node_ref.value {
-            NodeValue::Heading(heading) => {
-                let heading_content_node = if let Some(child) = node.first_child() {
-                    child
-                } else {
-                    continue;
-                };
+/// Text of a heading node along with its level
+fn heading_text<'a>(node: &'a AstNode<'a>) -> Option<(u8, String)> {
+    let level = match node.data.borrow().value {
+        NodeValue::Heading(heading) => heading.level,
+        _ => return None,
+    };
+    let child = node.first_child()?;
+    let child_ref = child.data.borrow();
+    match &child_ref.value {
+        NodeValue::Text(text) => Some((level, text.to_string())),
+        _ => None,
+    }
+}
 
-                let mut heading_content_ref = heading_content_node.data.borrow_mut();
-                if let NodeValue::Text(text) = &mut heading_content_ref.value {
-                    let re = Regex::new(r"Today's tasks \d+-\d+-\d+")
-                        .expect("title regex is not parsable");
-                    if matches!(re.find(text), Some(_)) {
-                        text.clear();
-                        text.push_str("Today's tasks ");
-                        text.push_str(new_date);
-                    } else if !sections.contains(text) {
-                        remove_heading(node, heading.level);
-                    };
-                }
+/// Names of all the sections in the document, in order
+pub fn section_names<'a>(root: &'a AstNode<'a>) -> Vec<String> {
+    root.children()
+        .filter_map(heading_text)
+        .filter(|(level, _)| *level >= 2)
+        .map(|(_, text)| text)
+        .collect()
+}
+
+/// Find the heading of the section named `section`
+pub fn find_section<'a>(root: &'a AstNode<'a>, section: &str) -> Option<&'a AstNode<'a>> {
+    root.children()
+        .find(|node| heading_text(node).is_some_and(|(level, text)| level >= 2 && text == section))
+}
+
+/// Find the list of tasks following a section heading
+pub fn section_list<'a>(heading: &'a AstNode<'a>) -> Option<&'a AstNode<'a>> {
+    let (level, _) = heading_text(heading)?;
+    heading
+        .following_siblings()
+        .skip(1)
+        .take_while(|sib| heading_text(sib).is_none_or(|(l, _)| l > level))
+        .find(|sib| matches!(sib.data.borrow().value, NodeValue::List(_)))
+}
+
+/// Append `text` to the text of a task item, before its block reference if
+/// it has one
+fn append_text<'a>(arena: &'a Arena<AstNode<'a>>, item: &'a AstNode<'a>, text: &str) {
+    let Some(paragraph) = item.first_child() else {
+        return;
+    };
+    if let Some(last) = paragraph.last_child() {
+        if let NodeValue::Text(last_text) = &mut last.data.borrow_mut().value {
+            match id_offset(last_text) {
+                Some(offset) => last_text.insert_str(offset, text),
+                None => last_text.push_str(text),
             }
-            NodeValue::List(_list) => remove_task_nodes(node),
-            _ => continue,
+            return;
         }
     }
-    eprintln!("{:#?}", root);
+    paragraph.append(
+        arena.alloc(AstNode::new(
+            Ast::new(
+                NodeValue::Text(text.into()),
+                LineColumn { line: 0, column: 0 },
+            )
+            .into(),
+        )),
+    );
+}
+
+/// First list among the extracted `nodes` of a section, adding one if there
+/// is none
+fn extracted_list<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    nodes: &mut Option<Vec<&'a AstNode<'a>>>,
+) -> &'a AstNode<'a> {
+    let nodes = nodes.get_or_insert_with(Vec::new);
+    match nodes
+        .iter()
+        .find(|node| matches!(node.data.borrow().value, NodeValue::List(_)))
+    {
+        Some(list) => list,
+        None => {
+            nodes.push(create_list(arena));
+            nodes.last().expect("list was just added")
+        }
+    }
+}
+
+fn create_list<'a>(arena: &'a Arena<AstNode<'a>>) -> &'a AstNode<'a> {
+    arena.alloc(AstNode::new(
+        Ast::new(
+            NodeValue::List(NodeList {
+                list_type: ListType::Bullet,
+                bullet_char: b'-',
+                padding: 2,
+                tight: true,
+                ..NodeList::default()
+            }),
+            LineColumn { line: 0, column: 0 },
+        )
+        .into(),
+    ))
+}
+
+/// Parse `text` as the contents of a new unchecked task item
+fn create_task_item<'a>(arena: &'a Arena<AstNode<'a>>, text: &str) -> Option<&'a AstNode<'a>> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let doc = parse_todo_file(&format!("- [ ] {}", text.trim()), arena);
+    let list = doc.first_child()?;
+    let item = list.first_child()?;
+    // the text has to make up exactly one task
+    if doc.children().count() != 1 || list.children().count() != 1 || Task::try_from(item).is_err()
+    {
+        return None;
+    }
+    item.detach();
+    Some(item)
+}
+
+/// Byte range of the 1 based `line` of `contents`, without its line ending
+fn line_range(contents: &str, line: usize) -> Option<Range<usize>> {
+    let mut start = 0;
+    for (i, text) in contents.split_inclusive('\n').enumerate() {
+        if i + 1 == line {
+            let text = text.trim_end_matches(['\n', '\r']);
+            return Some(start..start + text.len());
+        }
+        start += text.len();
+    }
+    None
+}
+
+/// Line ending used in `contents`
+fn line_ending(contents: &str) -> &'static str {
+    match contents.contains("\r\n") {
+        true => "\r\n",
+        false => "\n",
+    }
+}
+
+/// Marker of a new item at the end of `list`, e.g. `-` or `4.`
+fn next_list_marker<'a>(list: &'a AstNode<'a>) -> String {
+    let NodeValue::List(node_list) = list.data.borrow().value else {
+        return "-".into();
+    };
+    match node_list.list_type {
+        ListType::Bullet => char::from(node_list.bullet_char).to_string(),
+        ListType::Ordered => {
+            let delimiter = match node_list.delimiter {
+                ListDelimType::Period => '.',
+                ListDelimType::Paren => ')',
+            };
+            format!("{}{}", node_list.start + list.children().count(), delimiter)
+        }
+    }
+}
+
+/// Insert an unchecked task at the end of the list of the section named
+/// `section` in `contents`, the source `root` was parsed from. The list is
+/// started right below the heading if the section does not have one yet.
+/// Only the new line is added, the rest of `contents` is left as it is.
+pub fn add_task<'a>(
+    contents: &str,
+    root: &'a AstNode<'a>,
+    section: &str,
+    text: &str,
+) -> Result<String> {
+    let heading =
+        find_section(root, section).ok_or_else(|| Error::SectionNotFound(section.to_string()))?;
+    if create_task_item(&Arena::new(), text).is_none() {
+        return Err(Error::InvalidTask(text.to_string()));
+    }
+    let newline = line_ending(contents);
+    let not_found = || Error::SectionNotFound(section.to_string());
+
+    let (at, insert) = match section_list(heading) {
+        Some(list) => {
+            let (sourcepos, tight) = {
+                let list_ref = list.data.borrow();
+                let tight = matches!(list_ref.value, NodeValue::List(l) if l.tight);
+                (list_ref.sourcepos, tight)
+            };
+            let first = line_range(contents, sourcepos.start.line).ok_or_else(not_found)?;
+            let indent = &contents[first.start..first.start + sourcepos.start.column - 1];
+            // the end of a list counts the blank lines following it
+            let last = (sourcepos.start.line..=sourcepos.end.line)
+                .rev()
+                .filter_map(|line| line_range(contents, line))
+                .find(|range| !contents[range.clone()].trim().is_empty())
+                .ok_or_else(not_found)?;
+            let gap = if tight { "" } else { newline };
+            let item = format!("{}{} [ ] {}", indent, next_list_marker(list), text.trim());
+            (last.end, format!("{}{}{}", newline, gap, item))
+        }
+        None => {
+            let end = heading.data.borrow().sourcepos.end.line;
+            let line = line_range(contents, end).ok_or_else(not_found)?;
+            let followed =
+                line_range(contents, end + 1).is_some_and(|next| !contents[next].trim().is_empty());
+            let gap = if followed { newline } else { "" };
+            let item = format!("- [ ] {}", text.trim());
+            (line.end, format!("{}{}{}{}", newline, newline, item, gap))
+        }
+    };
+    Ok(format!("{}{}{}", &contents[..at], insert, &contents[at..]))
+}
+
+/// Collect the TaskGroups in `sections` in the order given, including
+/// completed tasks. Sections missing from the document are skipped.
+pub fn find_task_groups<'a>(root: &'a AstNode<'a>, sections: &[String]) -> Vec<TaskGroup> {
+    sections
+        .iter()
+        .filter_map(|section| {
+            let heading = find_section(root, section)?;
+            let (level, name) = heading_text(heading)?;
+            let tasks = section_list(heading)
+                .map(|list| {
+                    task_items(list)
+                        .filter_map(|item| Task::try_from(item).ok())
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(TaskGroup { name, tasks, level })
+        })
+        .collect()
+}
+
+/// A task item node along with its location in the document
+pub struct TaskRef<'a> {
+    pub section: String,
+    /// 1 based index of the task, followed by the indices of its subtasks
+    pub path: Vec<usize>,
+    pub node: &'a AstNode<'a>,
+}
+
+impl TaskRef<'_> {
+    /// Location of the task formatted as `Section 3.1`
+    pub fn location(&self) -> String {
+        let path: Vec<String> = self.path.iter().map(|i| i.to_string()).collect();
+        format!("{} {}", self.section, path.join("."))
+    }
+}
+
+/// Task items of a list, skipping plain list items
+fn task_items<'a>(list: &'a AstNode<'a>) -> impl Iterator<Item = &'a AstNode<'a>> {
+    list.children()
+        .filter(|item| matches!(item.data.borrow().value, NodeValue::TaskItem(_)))
+}
+
+/// Subtask items of a task item, across all of its nested lists
+fn subtask_items<'a>(item: &'a AstNode<'a>) -> impl Iterator<Item = &'a AstNode<'a>> {
+    item.children()
+        .filter(|child| matches!(child.data.borrow().value, NodeValue::List(_)))
+        .flat_map(task_items)
+}
+
+fn collect_task_refs<'a>(
+    items: impl Iterator<Item = &'a AstNode<'a>>,
+    section: &str,
+    parent: &[usize],
+    refs: &mut Vec<TaskRef<'a>>,
+) {
+    for (i, item) in items.enumerate() {
+        let path = [parent, &[i + 1]].concat();
+        refs.push(TaskRef {
+            section: section.to_string(),
+            path: path.clone(),
+            node: item,
+        });
+        collect_task_refs(subtask_items(item), section, &path, refs);
+    }
+}
+
+/// All tasks and subtasks in `sections`, in document order
+pub fn find_tasks<'a>(root: &'a AstNode<'a>, sections: &[String]) -> Vec<TaskRef<'a>> {
+    let mut refs = Vec::new();
+    for section in sections {
+        if let Some(list) = find_section(root, section).and_then(section_list) {
+            collect_task_refs(task_items(list), section, &[], &mut refs);
+        }
+    }
+    refs
+}
+
+/// Find a task by its 1 based path within a section (e.g. `[3, 1]` is the
+/// first subtask of the third task)
+pub fn find_task<'a>(
+    root: &'a AstNode<'a>,
+    section: &str,
+    path: &[usize],
+) -> Result<&'a AstNode<'a>> {
+    let not_found = || {
+        let path: Vec<String> = path.iter().map(|i| i.to_string()).collect();
+        Error::TaskNotFound(format!("{} {}", section, path.join(".")))
+    };
+    let heading =
+        find_section(root, section).ok_or_else(|| Error::SectionNotFound(section.to_string()))?;
+    let (first, rest) = path.split_first().ok_or_else(not_found)?;
+
+    let list = section_list(heading).ok_or_else(not_found)?;
+    let mut node = task_items(list)
+        .nth(first.wrapping_sub(1))
+        .ok_or_else(not_found)?;
+    for i in rest {
+        node = subtask_items(node)
+            .nth(i.wrapping_sub(1))
+            .ok_or_else(not_found)?;
+    }
+    Ok(node)
+}
+
+/// Find the single task in `sections` whose text contains `pattern`
+/// (ignoring case). Matching more than one task is an error.
+pub fn match_task<'a>(
+    root: &'a AstNode<'a>,
+    sections: &[String],
+    pattern: &str,
+) -> Result<&'a AstNode<'a>> {
+    let pattern_lower = pattern.to_lowercase();
+    let mut matches: Vec<TaskRef> = find_tasks(root, sections)
+        .into_iter()
+        .filter(|task_ref| {
+            Task::try_from(task_ref.node)
+                .is_ok_and(|task| task.text.to_lowercase().contains(&pattern_lower))
+        })
+        .collect();
+
+    match matches.len() {
+        0 => Err(Error::TaskNotFound(pattern.to_string())),
+        1 => Ok(matches.remove(0).node),
+        _ => Err(Error::AmbiguousTask {
+            pattern: pattern.to_string(),
+            matches: matches.iter().map(|task_ref| task_ref.location()).collect(),
+        }),
+    }
+}
+
+/// Rewrite the character between the brackets of a task item
+pub fn set_task_status<'a>(node: &'a AstNode<'a>, status: &TaskStatus) {
+    let mut node_ref = node.data.borrow_mut();
+    if let NodeValue::TaskItem(ch) = &mut node_ref.value {
+        *ch = status.task_char();
+    }
+}
+
+/// Rewrite the status of the task item `node` in `contents`, the source it
+/// was parsed from. Only the character between the brackets changes.
+pub fn replace_task_status<'a>(
+    contents: &str,
+    node: &'a AstNode<'a>,
+    status: &TaskStatus,
+) -> Result<String> {
+    let start = node.data.borrow().sourcepos.start;
+    let not_found = || Error::TaskNotFound(format!("line {}", start.line));
+    let line = line_range(contents, start.line).ok_or_else(not_found)?;
+    let from = line.start + start.column.saturating_sub(1);
+    let open = contents
+        .get(from..line.end)
+        .and_then(|text| text.find('['))
+        .ok_or_else(not_found)?
+        + from
+        + 1;
+    let close = contents[open..line.end].find(']').ok_or_else(not_found)? + open;
+    Ok(format!(
+        "{}{}{}",
+        &contents[..open],
+        status.task_char().unwrap_or(' '),
+        &contents[close..]
+    ))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::todo::{Status, Task};
+    use crate::todo::{Status, Task, TaskState};
     use std::io::BufWriter;
 
     #[test]
@@ -309,28 +958,28 @@ mod test {
     + [ ] subtask";
 
         let arena = Arena::new();
-        let root = parse_todo_file(&test_md.to_string(), &arena);
+        let root = parse_todo_file(test_md, &arena);
 
-        let result = extract_secitons(root, &vec![]);
+        let result = extract_task_groups(root, &[], &StatusMap::default());
         assert_eq!(result.keys().count(), 0);
 
-        let result = extract_secitons(root, &vec!["Not There".to_string()]);
+        let result = extract_task_groups(root, &["Not There".to_string()], &StatusMap::default());
         assert_eq!(result.keys().count(), 0);
 
         let sections = vec!["Unused".to_string()];
-        let result = extract_secitons(root, &sections);
+        let result = extract_task_groups(root, &sections, &StatusMap::default());
         assert_eq!(result.keys().count(), 0);
 
         let sections = vec!["Sub section".to_string()];
-        let result = extract_secitons(root, &sections);
+        let result = extract_task_groups(root, &sections, &StatusMap::default());
         assert_eq!(result.keys().count(), 1);
-        assert!(result.get(sections.first().unwrap()).is_some());
+        assert!(result.contains_key(sections.first().unwrap()));
         assert_eq!(result.get(sections.first().unwrap()).unwrap().level, 3);
 
         let sections = vec!["Content".to_string()];
-        let result = extract_secitons(root, &sections);
+        let result = extract_task_groups(root, &sections, &StatusMap::default());
         assert_eq!(result.keys().count(), 1);
-        assert!(result.get(sections.first().unwrap()).is_some());
+        assert!(result.contains_key(sections.first().unwrap()));
         assert_eq!(
             result
                 .get(sections.first().unwrap())
@@ -341,12 +990,16 @@ mod test {
                     Task {
                         status: TaskStatus::Empty,
                         text: "something".to_string(),
-                        subtasks: None
+                        line: Some(3),
+                        subtasks: None,
+                        ..Default::default()
                     },
                     Task {
                         status: TaskStatus::Todo('!'),
                         text: "other".to_string(),
-                        subtasks: None
+                        line: Some(5),
+                        subtasks: None,
+                        ..Default::default()
                     },
                 ],
                 level: 2
@@ -354,9 +1007,9 @@ mod test {
         );
 
         let sections = vec!["Unrealated Stuff".to_string()];
-        let result = extract_secitons(root, &sections);
+        let result = extract_task_groups(root, &sections, &StatusMap::default());
         assert_eq!(result.keys().count(), 1);
-        assert!(result.get(sections.first().unwrap()).is_some());
+        assert!(result.contains_key(sections.first().unwrap()));
         assert_eq!(
             result
                 .get(sections.first().unwrap())
@@ -366,21 +1019,63 @@ mod test {
                 tasks: vec![Task {
                     status: TaskStatus::Empty,
                     text: "something else".to_string(),
+                    line: Some(10),
                     subtasks: Some(vec![Task {
                         status: TaskStatus::Empty,
                         text: "subtask".to_string(),
-                        subtasks: None
+                        line: Some(11),
+                        subtasks: None,
+                        ..Default::default()
                     }]),
+                    ..Default::default()
                 }],
                 level: 2
             }
         );
 
-        let result = extract_secitons(
+        let result = extract_task_groups(
             root,
-            &vec!["Content".to_string(), "Sub section".to_string()],
+            &["Content".to_string(), "Sub section".to_string()],
+            &StatusMap::default(),
         );
         assert_eq!(result.keys().count(), 2);
+
+        // tasks with a status configured as cancelled are filtered out too
+        let sections = vec!["Content".to_string()];
+        let statuses = StatusMap::new([('!', TaskState::Cancelled)].into());
+        let result = extract_task_groups(root, &sections, &statuses);
+        let texts: Vec<&str> = result["Content"]
+            .tasks
+            .iter()
+            .map(|task| task.text.as_str())
+            .collect();
+        assert_eq!(texts, vec!["something"]);
+    }
+
+    #[test]
+    fn test_list_files() {
+        let dir = std::env::temp_dir().join(format!("rusty-tasks-list-{}", std::process::id()));
+        for path in [
+            "2024-01-01.md",
+            "archive/2024-01-02.md",
+            "2024/01/2024-01-03.md",
+            ".obsidian/app.json",
+        ] {
+            write_file(&dir.join(path), "").unwrap();
+        }
+        let list = |depth| {
+            let mut files: Vec<String> = list_files(&dir, depth)
+                .unwrap()
+                .iter()
+                .map(|path| path.strip_prefix(&dir).unwrap().display().to_string())
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(list(1), vec!["2024-01-01.md"]);
+        assert_eq!(list(2), vec!["archive/2024-01-02.md"]);
+        assert_eq!(list(3), vec!["2024/01/2024-01-03.md"]);
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
@@ -388,9 +1083,13 @@ mod test {
         let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
         let mut content: Vec<TaskGroup> = vec![];
 
-        let result = generate_file_content(&content, &date);
+        let result = generate_file_content(&content, &date, DEFAULT_TITLE_FORMAT);
         let expected = "# Today's tasks 2024-01-01\n";
         assert_eq!(result, expected);
+        assert_eq!(
+            generate_file_content(&content, &date, "%A %d %B"),
+            "# Monday 01 January\n"
+        );
 
         content.push(TaskGroup {
             name: "Empty".into(),
@@ -398,7 +1097,7 @@ mod test {
             level: 2,
         });
 
-        let result = generate_file_content(&content, &date);
+        let result = generate_file_content(&content, &date, DEFAULT_TITLE_FORMAT);
         let expected = "# Today's tasks 2024-01-01\n\n## Empty\n";
         assert_eq!(result, expected);
 
@@ -408,7 +1107,7 @@ mod test {
             level: 3,
         });
 
-        let result = generate_file_content(&content, &date);
+        let result = generate_file_content(&content, &date, DEFAULT_TITLE_FORMAT);
         let expected = "# Today's tasks 2024-01-01\n\n## Empty\n\n### Subgroup\n";
         assert_eq!(result, expected);
 
@@ -419,22 +1118,25 @@ mod test {
                     status: Status::Empty,
                     text: "task 1".into(),
                     subtasks: None,
+                    ..Default::default()
                 },
                 Task {
                     status: Status::Done('x'),
                     text: "task 2".into(),
                     subtasks: None,
+                    ..Default::default()
                 },
                 Task {
                     status: Status::Todo('>'),
                     text: "task 3".into(),
                     subtasks: None,
+                    ..Default::default()
                 },
             ],
             level: 2,
         });
 
-        let result = generate_file_content(&content, &date);
+        let result = generate_file_content(&content, &date, DEFAULT_TITLE_FORMAT);
         let expected = "\
 # Today's tasks 2024-01-01
 
@@ -479,7 +1181,6 @@ mod test {
 - some notes here
 - these can go
 ";
-        let new_date = "2024-01-02";
         let groups = vec![
             "Tasks".to_string(),
             "Other".to_string(),
@@ -501,11 +1202,9 @@ mod test {
 
         let ast = parse_document(&arena, md, options);
 
-        let sections = extract_sections(ast, &groups);
+        let sections = extract_sections(ast, &groups, &StatusMap::default());
 
-        let new_doc = create_new_doc(&arena, new_date, sections);
-
-        process_doc_tree(ast, new_date, &groups);
+        let new_doc = create_new_doc(&arena, "Today's tasks 2024-01-02", sections, false);
 
         let mut output = BufWriter::new(Vec::new());
 
@@ -536,4 +1235,439 @@ mod test {
             text
         );
     }
+
+    #[test]
+    fn test_add_task() {
+        let md = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+* [ ] call [[Bob]] about #meeting
+* [x] fix second_task, 1 < 2
+  * [ ] subtask
+
+## Weekly
+
+Some notes
+
+## Monthly
+
+1. [ ] pay rent
+2. [ ] taxes
+
+## Yearly
+";
+        let add = |contents: &str, section, text| {
+            let arena = Arena::new();
+            let root = parse_todo_file(contents, &arena);
+            add_task(contents, root, section, text)
+        };
+
+        let mut contents = md.to_string();
+        for (section, text) in [
+            ("Daily", "task 3"),
+            ("Weekly", "review PR"),
+            ("Monthly", "*plan* trip"),
+            ("Yearly", "see [[Alice]]"),
+        ] {
+            contents = add(&contents, section, text).expect("should add the task");
+        }
+        assert_eq!(
+            contents,
+            "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+* [ ] call [[Bob]] about #meeting
+* [x] fix second_task, 1 < 2
+  * [ ] subtask
+* [ ] task 3
+
+## Weekly
+
+- [ ] review PR
+
+Some notes
+
+## Monthly
+
+1. [ ] pay rent
+2. [ ] taxes
+3. [ ] *plan* trip
+
+## Yearly
+
+- [ ] see [[Alice]]
+"
+        );
+
+        assert!(matches!(
+            add(md, "Decade", "nope"),
+            Err(Error::SectionNotFound(_))
+        ));
+        assert!(matches!(add(md, "Daily", ""), Err(Error::InvalidTask(_))));
+        assert!(matches!(
+            add(md, "Daily", "one\n- [ ] two"),
+            Err(Error::InvalidTask(_))
+        ));
+        assert_eq!(
+            add(
+                "## Daily\r\n\r\n1) [ ] one\r\n\r\n   notes\r\n",
+                "Daily",
+                "two"
+            )
+            .unwrap(),
+            "## Daily\r\n\r\n1) [ ] one\r\n\r\n   notes\r\n\r\n2) [ ] two\r\n"
+        );
+
+        let arena = Arena::new();
+        let root = parse_todo_file(&contents, &arena);
+        let heading = find_section(root, "Daily").expect("should find Daily");
+        let group = TaskGroup::try_from(heading).expect("should parse Daily");
+        assert_eq!(group.tasks.len(), 3);
+        assert_eq!(group.tasks[2].text, "task 3");
+    }
+
+    #[test]
+    fn test_find_and_set_task() {
+        let md = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] deploy api
+- [x] write tests
+  - [ ] unit
+  - [>] integration
+- [ ] deploy web
+
+## Weekly
+
+- [ ] review PR
+";
+        let arena = Arena::new();
+        let root = parse_todo_file(md, &arena);
+        let sections = vec!["Daily".to_string(), "Weekly".to_string()];
+
+        let locations: Vec<String> = find_tasks(root, &sections)
+            .iter()
+            .map(|task_ref| task_ref.location())
+            .collect();
+        assert_eq!(
+            locations,
+            vec![
+                "Daily 1",
+                "Daily 2",
+                "Daily 2.1",
+                "Daily 2.2",
+                "Daily 3",
+                "Weekly 1"
+            ]
+        );
+
+        let node = find_task(root, "Daily", &[2, 2]).expect("should find 2.2");
+        assert_eq!(Task::try_from(node).unwrap().text, "integration");
+        assert!(matches!(
+            find_task(root, "Daily", &[4]),
+            Err(Error::TaskNotFound(_))
+        ));
+        assert!(matches!(
+            find_task(root, "Daily", &[0]),
+            Err(Error::TaskNotFound(_))
+        ));
+        assert!(matches!(
+            find_task(root, "Monthly", &[1]),
+            Err(Error::SectionNotFound(_))
+        ));
+
+        match match_task(root, &sections, "Deploy") {
+            Err(Error::AmbiguousTask { matches, .. }) => {
+                assert_eq!(matches, vec!["Daily 1", "Daily 3"])
+            }
+            _ => panic!("expected ambiguous match"),
+        }
+        assert!(matches!(
+            match_task(root, &sections, "nothing"),
+            Err(Error::TaskNotFound(_))
+        ));
+
+        let node = match_task(root, &sections, "deploy web").expect("should match");
+        set_task_status(node, &TaskStatus::Done('x'));
+        let node = find_task(root, "Daily", &[2]).unwrap();
+        set_task_status(node, &TaskStatus::Empty);
+        let node = find_task(root, "Daily", &[2, 1]).unwrap();
+        set_task_status(node, &TaskStatus::Todo('/'));
+
+        assert_eq!(
+            render_doc(root).unwrap(),
+            "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] deploy api
+- [ ] write tests
+  - [/] unit
+  - [>] integration
+- [x] deploy web
+
+## Weekly
+
+- [ ] review PR
+"
+        );
+
+        // only the status changes in the source, however it is written
+        let md = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+* [ ] call [[Bob]] about #meeting
+*   [x] fix second_task, 1 < 2
+    + [ ] subtask
+
+1. [ ] not in a section's first list
+
+## Weekly
+
+ 1) [ ] review PR
+";
+        let sections = vec!["Daily".to_string()];
+        let set = |contents: &str, pattern: &str, status| {
+            let arena = Arena::new();
+            let root = parse_todo_file(contents, &arena);
+            let node = match_task(root, &sections, pattern).expect("should match");
+            replace_task_status(contents, node, &status).expect("should set the status")
+        };
+        let contents = set(md, "bob", TaskStatus::Done('x'));
+        let contents = set(&contents, "second", TaskStatus::Empty);
+        let contents = set(&contents, "subtask", TaskStatus::Todo('/'));
+        assert_eq!(
+            contents,
+            md.replace("* [ ] call", "* [x] call")
+                .replace("[x] fix", "[ ] fix")
+                .replace("+ [ ]", "+ [/]")
+        );
+
+        let arena = Arena::new();
+        let root = parse_todo_file(md, &arena);
+        let contents = add_task(md, root, "Daily", "write #docs for [[Alice]]").unwrap();
+        let contents = set(&contents, "alice", TaskStatus::Done('x'));
+        assert_eq!(
+            contents,
+            md.replace(
+                "    + [ ] subtask\n",
+                "    + [ ] subtask\n* [x] write #docs for [[Alice]]\n"
+            )
+        );
+        let arena = Arena::new();
+        let root = parse_todo_file(&contents, &arena);
+        let node = find_task(root, "Weekly", &[1]).unwrap();
+        assert_eq!(
+            replace_task_status(&contents, node, &TaskStatus::Done('X')).unwrap(),
+            contents.replace(" 1) [ ] review", " 1) [X] review")
+        );
+    }
+
+    #[test]
+    fn test_find_task_groups() {
+        let md = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+Some notes
+
+- [ ] task 1
+- [x] task 2
+  - [ ] subtask
+
+## Weekly
+
+## Notes
+
+- [ ] not a section
+";
+        let arena = Arena::new();
+        let root = parse_todo_file(md, &arena);
+        let sections = vec![
+            "Weekly".to_string(),
+            "Daily".to_string(),
+            "Monthly".to_string(),
+        ];
+
+        let groups = find_task_groups(root, &sections);
+        assert_eq!(
+            groups,
+            vec![
+                TaskGroup::empty("Weekly".into(), 2),
+                TaskGroup {
+                    name: "Daily".into(),
+                    tasks: vec![
+                        Task {
+                            status: TaskStatus::Empty,
+                            text: "task 1".into(),
+                            line: Some(7),
+                            subtasks: None,
+                            ..Default::default()
+                        },
+                        Task {
+                            status: TaskStatus::Done('x'),
+                            text: "task 2".into(),
+                            line: Some(8),
+                            subtasks: Some(vec![Task {
+                                status: TaskStatus::Empty,
+                                text: "subtask".into(),
+                                line: Some(9),
+                                subtasks: None,
+                                ..Default::default()
+                            }]),
+                            ..Default::default()
+                        },
+                    ],
+                    level: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_by_priority() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] first
+- [ ] low 🔽
+- [ ] (A) high
+  - [ ] sub 🔺
+  - [ ] sub
+- plain item
+- [ ] second
+- [ ] highest 🔺
+- [x] done 🔺
+";
+        let mut options = CarryOptions::new(&["Daily".to_string()]);
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert!(content.contains("- [ ] first\n- [ ] low 🔽\n"));
+
+        options.sort_by_priority = true;
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-02
+
+## Daily
+
+- [ ] highest 🔺
+- [ ] (A) high
+  - [ ] sub 🔺
+  - [ ] sub
+- [ ] first
+- plain item
+- [ ] second
+- [ ] low 🔽
+"
+        );
+    }
+
+    #[test]
+    fn test_carry_statuses() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [-] cancelled
+  - [ ] subtask of cancelled
+- [/] in progress
+  - [-] cancelled subtask
+- [>] deferred
+- [x] done
+- [-] cancelled 🔁 every day
+";
+        let mut options = CarryOptions::new(&["Daily".to_string()]);
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert!(content.contains("- [-] cancelled\n"));
+
+        options.statuses = StatusMap::new(
+            [
+                ('-', TaskState::Cancelled),
+                ('/', TaskState::InProgress),
+                ('>', TaskState::Deferred),
+            ]
+            .into(),
+        );
+        let (content, report) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-02
+
+## Daily
+
+- [/] in progress
+- [>] deferred
+"
+        );
+        assert_eq!(report.sections[0].cancelled, 4);
+        assert_eq!(report.sections[0].recurring, 0);
+    }
+
+    #[test]
+    fn test_carry_title() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let mut options = CarryOptions::new(&["Daily".to_string()]);
+        options.title_format = "Tasks %Y-%m-%d".into();
+
+        let contents = "# Tasks 2024-01-01 at the *offsite*\n\n## Daily\n\n- [ ] task\n";
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert_eq!(
+            content,
+            "# Tasks 2024-01-02 at the *offsite*\n\n## Daily\n\n- [ ] task\n"
+        );
+
+        // titles in another format are replaced
+        let contents = "# Today's tasks 2024-01-01 at the *offsite*\n\n## Daily\n";
+        let (content, _) = carry_over_with_report(contents, &date, &date, &options).unwrap();
+        assert_eq!(content, "# Tasks 2024-01-02\n\n## Daily\n");
+    }
+
+    #[test]
+    fn test_carry_tags() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] write #work @office
+  - [ ] #docs/api first
+- [ ] fix issue #12
+";
+        let content = carry_over(contents, &date, &["Daily".to_string()]).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-02
+
+## Daily
+
+- [ ] write #work @office
+  - [ ] #docs/api first
+- [ ] fix issue #12
+"
+        );
+        assert_eq!(
+            carry_over(&content, &date, &["Daily".to_string()]).unwrap(),
+            content
+        );
+    }
 }
@@ -0,0 +1,138 @@
+use super::{extracted_list, task_items, CarryOptions};
+use crate::todo::Task;
+use chrono::NaiveDate;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+use indexmap::IndexMap;
+
+/// Move unfinished tasks with a due date into the section whose horizon
+/// contains it, counting days from `date`. Overdue tasks go to the section
+/// with the shortest horizon, tasks due after every horizon stay where they
+/// are. Only tasks in sections that have a horizon are moved.
+pub(super) fn promote_tasks<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    sections: &mut IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    date: &NaiveDate,
+    options: &CarryOptions,
+) {
+    if options.horizons.is_empty() {
+        return;
+    }
+    let horizons: Vec<Option<i64>> = sections
+        .keys()
+        .map(|name| options.horizons.get(name).map(|h| i64::from(*h)))
+        .collect();
+    // section with the shortest horizon containing a task due in `days`
+    let target = |days: i64| {
+        horizons
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| h.filter(|h| days <= *h).map(|h| (i, h)))
+            .min_by_key(|(_, h)| *h)
+            .map(|(i, _)| i)
+    };
+
+    let mut moves = Vec::new();
+    for (i, nodes) in sections.values().enumerate() {
+        if horizons[i].is_none() {
+            continue;
+        }
+        let items = nodes
+            .iter()
+            .flatten()
+            .filter(|node| matches!(node.data.borrow().value, NodeValue::List(_)))
+            .flat_map(|list| task_items(list));
+
+        for item in items {
+            let Some(due) = Task::try_from(item).ok().and_then(|t| t.dates.due) else {
+                continue;
+            };
+            match target((due - *date).num_days()) {
+                Some(j) if j != i => moves.push((j, item)),
+                _ => {}
+            }
+        }
+    }
+
+    for (i, item) in moves {
+        let (name, nodes) = sections
+            .get_index_mut(i)
+            .expect("moving to an existing section");
+        log::debug!(
+            "moving task on line {} to {}",
+            item.data.borrow().sourcepos.start.line,
+            name
+        );
+        extracted_list(arena, nodes).append(item);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::carry_over_with_report;
+
+    #[test]
+    fn test_promote_tasks() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-10
+
+## Daily
+
+- [ ] due later 📅 2024-01-20
+
+## Weekly
+
+- [ ] due tomorrow due:2024-01-12
+- [ ] no due date
+
+## Monthly
+
+- [ ] overdue 📅 2024-01-02
+- [ ] due next week 📅 2024-01-15
+- [ ] due next year 📅 2025-01-15
+
+## Someday
+
+- [ ] not moved 📅 2024-01-11
+";
+        let sections: Vec<String> = ["Daily", "Weekly", "Monthly", "Someday"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut options = CarryOptions::new(&sections);
+        options.horizons = [("Daily", 1), ("Weekly", 7), ("Monthly", 31)]
+            .into_iter()
+            .map(|(name, days)| (name.to_string(), days))
+            .collect();
+
+        let (content, _) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-11
+
+## Daily
+
+- [ ] due tomorrow due:2024-01-12
+- [ ] overdue 📅 2024-01-02
+
+## Weekly
+
+- [ ] no due date
+- [ ] due next week 📅 2024-01-15
+
+## Monthly
+
+- [ ] due next year 📅 2025-01-15
+- [ ] due later 📅 2024-01-20
+
+## Someday
+
+- [ ] not moved 📅 2024-01-11
+"
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use super::{append_text, find_tasks, set_task_status};
+use crate::todo::{
+    due_marker, shift_dates, strip_completion, Recurrence, Status, StatusMap, Task, TaskState,
+};
+use chrono::NaiveDate;
+use comrak::nodes::{AstNode, NodeValue};
+use comrak::Arena;
+
+/// Turn completed recurring tasks in `sections`, but not cancelled ones, back
+/// into unchecked tasks for their next occurrence so carry-over keeps them.
+/// Returns how many tasks were renewed in each of `sections`.
+///
+/// The next occurrence follows the due date of the task, or its scheduled,
+/// start or completion date, or `from` (the date of the file) when it has
+/// none. Due, scheduled and start dates are moved to the next occurrence, a
+/// due date is added when the task has none of them. Created and completion
+/// dates are removed.
+pub(super) fn renew_recurring<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    root: &'a AstNode<'a>,
+    from: &NaiveDate,
+    sections: &[String],
+    statuses: &StatusMap,
+) -> Vec<usize> {
+    let mut renewed = vec![0; sections.len()];
+    let tasks = find_tasks(root, sections)
+        .into_iter()
+        .filter(|task_ref| task_ref.path.len() == 1);
+
+    for task_ref in tasks {
+        let Ok(task) = Task::try_from(task_ref.node) else {
+            continue;
+        };
+        if statuses.status_state(&task.status) != TaskState::Done {
+            continue;
+        }
+        let Some(recurrence) = Recurrence::parse(&task.text) else {
+            continue;
+        };
+
+        let dates = &task.dates;
+        let planned = dates.due.or(dates.scheduled).or(dates.start);
+        let last = planned.or(dates.completed).unwrap_or(*from);
+        let next = recurrence.next(&last);
+        let days = (next - last).num_days();
+
+        let item = task_ref.node;
+        for node in item.first_child().into_iter().flat_map(|p| p.descendants()) {
+            if let NodeValue::Text(text) = &mut node.data.borrow_mut().value {
+                *text = shift_dates(&strip_completion(text), days);
+            }
+        }
+        if planned.is_none() {
+            append_text(arena, item, &due_marker(&next));
+        }
+        for node in item.descendants() {
+            set_task_status(node, &Status::Empty);
+        }
+
+        if let Some(i) = sections.iter().position(|s| *s == task_ref.section) {
+            renewed[i] += 1;
+        }
+    }
+    renewed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::{carry_over_with_report, CarryOptions};
+
+    #[test]
+    fn test_renew_recurring() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-10
+
+## Daily
+
+- [x] water plants 🔁 every 3 days
+- [x] weekly report 🔁 every monday 📅 2024-01-08 ➕ 2024-01-01 ✅ 2024-01-10
+  - [x] send it
+- [x] pay rent 🔁 every month on the 1st ⏳ 2024-01-01
+- [x] one off task
+- [ ] open task 🔁 every day
+";
+        let options = CarryOptions::new(&["Daily".to_string()]);
+        let (content, report) =
+            carry_over_with_report(contents, &date(10), &date(11), &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# Today's tasks 2024-01-11
+
+## Daily
+
+- [ ] water plants 🔁 every 3 days 📅 2024-01-13
+- [ ] weekly report 🔁 every monday 📅 2024-01-15
+  - [ ] send it
+- [ ] pay rent 🔁 every month on the 1st ⏳ 2024-02-01
+- [ ] open task 🔁 every day
+"
+        );
+        assert_eq!(report.sections[0].recurring, 3);
+        // renewed tasks and their subtasks are carried, not dropped
+        assert_eq!(report.sections[0].carried, 5);
+        assert_eq!(report.sections[0].dropped, 1);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::todo::{StatusMap, TaskState};
+use comrak::nodes::{AstNode, NodeValue};
+use serde::Serialize;
+use std::fmt;
+
+/// Number of tasks moved from one section during carry-over
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SectionReport {
+    pub name: String,
+    /// unfinished tasks copied into the new file
+    pub carried: usize,
+    /// completed tasks (and their subtasks) that were not copied
+    pub dropped: usize,
+    /// cancelled tasks (and their subtasks) that were not copied
+    pub cancelled: usize,
+    /// unfinished tasks merged in from earlier files
+    pub merged: usize,
+    /// completed recurring tasks renewed for their next occurrence
+    pub recurring: usize,
+}
+
+/// Summary of what carry-over does to a notes file
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CarryReport {
+    pub sections: Vec<SectionReport>,
+    /// unfinished tasks outside of the carried sections
+    pub left_behind: usize,
+}
+
+impl CarryReport {
+    /// Count the tasks carry-over would carry, drop and leave behind in the
+    /// document `root` for the given `sections`, with the meaning of status
+    /// characters given by `statuses`
+    pub fn new<'a>(root: &'a AstNode<'a>, sections: &[String], statuses: &StatusMap) -> Self {
+        let mut report = CarryReport {
+            sections: sections
+                .iter()
+                .map(|name| SectionReport {
+                    name: name.clone(),
+                    ..SectionReport::default()
+                })
+                .collect(),
+            left_behind: 0,
+        };
+
+        // index into report.sections and heading level of the current section
+        let mut current: Option<(usize, u8)> = None;
+        for node in root.children() {
+            if let NodeValue::Heading(heading) = node.data.borrow().value {
+                let title = node
+                    .first_child()
+                    .and_then(|child| match &child.data.borrow().value {
+                        NodeValue::Text(text) => Some(text.clone()),
+                        _ => None,
+                    });
+                if let Some(i) = title.and_then(|t| sections.iter().position(|s| *s == t)) {
+                    current = Some((i, heading.level));
+                } else if current.is_some_and(|(_, level)| heading.level <= level) {
+                    current = None;
+                }
+                continue;
+            }
+
+            match current {
+                Some((i, _)) => count_tasks(node, None, statuses, &mut report.sections[i]),
+                None => {
+                    let mut other = SectionReport::default();
+                    count_tasks(node, None, statuses, &mut other);
+                    report.left_behind += other.carried;
+                }
+            }
+        }
+        report
+    }
+
+    /// Record the number of renewed recurring tasks of each section, in the
+    /// order of `sections`. The report is expected to be made after renewal
+    /// so they are already counted as carried.
+    pub(super) fn add_recurring(&mut self, counts: &[usize]) {
+        for (section, count) in self.sections.iter_mut().zip(counts) {
+            section.recurring += count;
+        }
+    }
+}
+
+/// count unfinished, done and cancelled task items under `node` into
+/// `counts`, items under a finished task are counted the same as that task
+fn count_tasks<'a>(
+    node: &'a AstNode<'a>,
+    finished: Option<TaskState>,
+    statuses: &StatusMap,
+    counts: &mut SectionReport,
+) {
+    for child in node.children() {
+        let finished = match child.data.borrow().value {
+            NodeValue::TaskItem(ch) => {
+                let state = finished.unwrap_or_else(|| statuses.state(ch));
+                match state {
+                    TaskState::Done => counts.dropped += 1,
+                    TaskState::Cancelled => counts.cancelled += 1,
+                    _ => counts.carried += 1,
+                }
+                Some(state).filter(TaskState::is_finished)
+            }
+            _ => finished,
+        };
+        count_tasks(child, finished, statuses, counts);
+    }
+}
+
+impl fmt::Display for CarryReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in &self.sections {
+            write!(
+                f,
+                "{}: {} carried, {} dropped as done",
+                section.name, section.carried, section.dropped
+            )?;
+            if section.cancelled > 0 {
+                write!(f, ", {} cancelled", section.cancelled)?;
+            }
+            if section.merged > 0 {
+                write!(f, ", {} merged from earlier files", section.merged)?;
+            }
+            if section.recurring > 0 {
+                write!(f, ", {} recurring", section.recurring)?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "{} unfinished tasks left behind in other sections",
+            self.left_behind
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::parse_todo_file;
+    use comrak::Arena;
+
+    #[test]
+    fn test_carry_report() {
+        let md = "\
+# Today's tasks 2024-01-01
+
+- [ ] before any section
+
+## Daily
+
+- [ ] task 1
+- [x] task 2
+  - [ ] subtask of done task
+- [>] task 3
+  - [X] done subtask
+
+### Sub section
+
+- [ ] nested task
+
+## Notes
+
+- [ ] left behind
+- [x] done and left behind
+
+## Weekly
+
+- [ ] weekly
+";
+        let arena = Arena::new();
+        let root = parse_todo_file(md, &arena);
+        let sections = vec![
+            "Daily".to_string(),
+            "Weekly".to_string(),
+            "Monthly".to_string(),
+        ];
+
+        let report = CarryReport::new(root, &sections, &StatusMap::default());
+        assert_eq!(
+            report,
+            CarryReport {
+                sections: vec![
+                    SectionReport {
+                        name: "Daily".into(),
+                        carried: 3,
+                        dropped: 3,
+                        cancelled: 0,
+                        merged: 0,
+                        recurring: 0,
+                    },
+                    SectionReport {
+                        name: "Weekly".into(),
+                        carried: 1,
+                        dropped: 0,
+                        cancelled: 0,
+                        merged: 0,
+                        recurring: 0,
+                    },
+                    SectionReport {
+                        name: "Monthly".into(),
+                        carried: 0,
+                        dropped: 0,
+                        cancelled: 0,
+                        merged: 0,
+                        recurring: 0,
+                    },
+                ],
+                left_behind: 2,
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "\
+Daily: 3 carried, 3 dropped as done
+Weekly: 1 carried, 0 dropped as done
+Monthly: 0 carried, 0 dropped as done
+2 unfinished tasks left behind in other sections
+"
+        );
+
+        // subtasks of a cancelled task count as cancelled
+        let statuses = StatusMap::new([('>', TaskState::Cancelled)].into());
+        let report = CarryReport::new(root, &sections, &statuses);
+        assert_eq!(
+            report.sections[0],
+            SectionReport {
+                name: "Daily".into(),
+                carried: 2,
+                dropped: 2,
+                cancelled: 2,
+                merged: 0,
+                recurring: 0,
+            }
+        );
+        assert!(report
+            .to_string()
+            .starts_with("Daily: 2 carried, 2 dropped as done, 2 cancelled\n"));
+    }
+}
@@ -0,0 +1,296 @@
+use super::{create_heading, create_list, create_task_item, render_doc, set_task_status};
+use crate::error::Result;
+use crate::todo::{format_date, File as TodoFile, StatusMap, Task, TaskGroup, TaskState};
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use comrak::nodes::{Ast, AstNode, LineColumn, NodeValue};
+use comrak::Arena;
+use std::path::{Path, PathBuf};
+
+/// Days covered by a review
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReviewPeriod {
+    /// ISO week, from Monday to Sunday
+    Week {
+        year: i32,
+        week: u32,
+    },
+    Month {
+        year: i32,
+        month: u32,
+    },
+}
+
+impl ReviewPeriod {
+    /// ISO week containing `date`
+    pub fn week(date: &NaiveDate) -> Self {
+        let week = date.iso_week();
+        ReviewPeriod::Week {
+            year: week.year(),
+            week: week.week(),
+        }
+    }
+
+    /// Month containing `date`
+    pub fn month(date: &NaiveDate) -> Self {
+        ReviewPeriod::Month {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+
+    /// First day of the period
+    pub fn start(&self) -> NaiveDate {
+        match *self {
+            ReviewPeriod::Week { year, week } => {
+                NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            }
+            ReviewPeriod::Month { year, month } => NaiveDate::from_ymd_opt(year, month, 1),
+        }
+        .expect("a valid period")
+    }
+
+    /// Last day of the period
+    pub fn end(&self) -> NaiveDate {
+        match self {
+            ReviewPeriod::Week { .. } => self.start() + Days::new(6),
+            ReviewPeriod::Month { .. } => self.start() + Months::new(1) - Days::new(1),
+        }
+    }
+
+    /// whether `date` falls in the period
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        (self.start()..=self.end()).contains(date)
+    }
+
+    /// Name of the period, `2026-W42` for a week and `2026-10` for a month
+    pub fn name(&self) -> String {
+        match self {
+            ReviewPeriod::Week { year, week } => format!("{}-W{:02}", year, week),
+            ReviewPeriod::Month { year, month } => format!("{}-{:02}", year, month),
+        }
+    }
+}
+
+/// Path of the review file for `period`, in the directory the notes file of
+/// the first day of the period goes in according to `file_pattern`
+pub fn review_filepath(data_dir: &Path, period: &ReviewPeriod, file_pattern: &str) -> PathBuf {
+    let notes_file = format_date(&period.start(), file_pattern).unwrap_or_default();
+    let dir = Path::new(&notes_file).parent().unwrap_or(Path::new(""));
+    data_dir.join(dir).join(format!("{}.md", period.name()))
+}
+
+fn create_paragraph<'a>(arena: &'a Arena<AstNode<'a>>, text: &str) -> &'a AstNode<'a> {
+    let paragraph = arena.alloc(AstNode::new(
+        Ast::new(NodeValue::Paragraph, LineColumn { line: 0, column: 0 }).into(),
+    ));
+    paragraph.append(
+        arena.alloc(AstNode::new(
+            Ast::new(
+                NodeValue::Text(text.to_string()),
+                LineColumn { line: 0, column: 0 },
+            )
+            .into(),
+        )),
+    );
+    paragraph
+}
+
+/// List of `tasks` keeping their status. Tasks whose text does not make up
+/// a single list item, such as text over several lines, are left out with a
+/// warning.
+fn create_task_list<'a>(arena: &'a Arena<AstNode<'a>>, tasks: &[&Task]) -> &'a AstNode<'a> {
+    let list = create_list(arena);
+    for task in tasks {
+        match create_task_item(arena, &task.text) {
+            Some(item) => {
+                set_task_status(item, &task.status);
+                list.append(item);
+            }
+            None => log::warn!("leaving task out of the review: {:?}", task.text),
+        }
+    }
+    list
+}
+
+/// `tasks` and their subtasks that are done according to `statuses`
+fn done_tasks<'a>(tasks: &'a [Task], statuses: &StatusMap, out: &mut Vec<&'a Task>) {
+    for task in tasks {
+        if statuses.status_state(&task.status) == TaskState::Done {
+            out.push(task);
+        }
+        done_tasks(task.subtasks.as_deref().unwrap_or_default(), statuses, out);
+    }
+}
+
+/// Markdown review of the `files` in `period`: the tasks completed in
+/// `sections` on each day, followed by the tasks of each section still open
+/// in the last file of the period.
+pub fn create_review<'a, I>(
+    files: I,
+    period: &ReviewPeriod,
+    sections: &[String],
+    statuses: &StatusMap,
+) -> Result<String>
+where
+    I: IntoIterator<Item = (&'a TodoFile, &'a [TaskGroup])>,
+{
+    let mut files: Vec<_> = files
+        .into_iter()
+        .filter(|(file, _)| period.contains(&file.date))
+        .collect();
+    files.sort_by_key(|(file, _)| file.date);
+    let in_sections = |group: &&TaskGroup| sections.contains(&group.name);
+
+    let arena = Arena::new();
+    let doc = arena.alloc(AstNode::new(
+        Ast::new(NodeValue::Document, LineColumn { line: 0, column: 0 }).into(),
+    ));
+    doc.append(create_heading(
+        &arena,
+        1,
+        &format!("Review {}", period.name()),
+    ));
+    doc.append(create_paragraph(
+        &arena,
+        &format!("{} to {}", period.start(), period.end()),
+    ));
+
+    doc.append(create_heading(&arena, 2, "Completed"));
+    for (file, groups) in &files {
+        let mut done = Vec::new();
+        for group in groups.iter().filter(in_sections) {
+            done_tasks(&group.tasks, statuses, &mut done);
+        }
+        if done.is_empty() {
+            continue;
+        }
+        doc.append(create_heading(&arena, 3, &file.date.to_string()));
+        doc.append(create_task_list(&arena, &done));
+    }
+
+    doc.append(create_heading(&arena, 2, "Open"));
+    if let Some((_, groups)) = files.last() {
+        for group in groups.iter().filter(in_sections) {
+            let open: Vec<&Task> = group
+                .tasks
+                .iter()
+                .filter(|task| !statuses.status_state(&task.status).is_finished())
+                .collect();
+            if open.is_empty() {
+                continue;
+            }
+            doc.append(create_heading(&arena, 3, &group.name));
+            doc.append(create_task_list(&arena, &open));
+        }
+    }
+    render_doc(doc)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::todo::Status;
+
+    #[test]
+    fn test_create_review() {
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let week = ReviewPeriod::week(&date("2026-10-17"));
+        assert_eq!(week.name(), "2026-W42");
+        assert_eq!(week.start(), date("2026-10-12"));
+        assert_eq!(week.end(), date("2026-10-18"));
+        let month = ReviewPeriod::month(&date("2024-02-10"));
+        assert_eq!(month.name(), "2024-02");
+        assert_eq!(month.end(), date("2024-02-29"));
+        assert!(month.contains(&date("2024-02-01")));
+        assert!(!month.contains(&date("2024-03-01")));
+        assert_eq!(
+            review_filepath(Path::new("/notes"), &week, "%Y-%m-%d.md"),
+            PathBuf::from("/notes/2026-W42.md")
+        );
+        assert_eq!(
+            review_filepath(Path::new("/notes"), &month, "%Y/%m/%Y-%m-%d.md"),
+            PathBuf::from("/notes/2024/02/2024-02.md")
+        );
+
+        let file = |d: &str| TodoFile::try_from(PathBuf::from(format!("/notes/{}.md", d))).unwrap();
+        let task = |status, text: &str, subtasks| Task {
+            status,
+            text: text.into(),
+            subtasks,
+            ..Default::default()
+        };
+        let group = |name: &str, tasks| TaskGroup {
+            name: name.into(),
+            tasks,
+            level: 2,
+        };
+        let files = [
+            (
+                file("2026-10-11"),
+                vec![group(
+                    "Daily",
+                    vec![task(Status::Done('x'), "last week", None)],
+                )],
+            ),
+            (
+                file("2026-10-13"),
+                vec![
+                    group(
+                        "Daily",
+                        vec![
+                            task(
+                                Status::Empty,
+                                "write RFC",
+                                Some(vec![task(Status::Done('x'), "outline", None)]),
+                            ),
+                            task(Status::Done('x'), "buy milk", None),
+                            task(Status::Todo('-'), "call bob", None),
+                        ],
+                    ),
+                    group("Notes", vec![task(Status::Done('x'), "not a task", None)]),
+                ],
+            ),
+            (
+                file("2026-10-14"),
+                vec![
+                    group("Daily", vec![task(Status::Done('X'), "write RFC", None)]),
+                    group("Weekly", vec![task(Status::Todo('/'), "review PR", None)]),
+                ],
+            ),
+        ];
+        let sections = vec!["Daily".to_string(), "Weekly".to_string()];
+        let statuses = StatusMap::new([('-', TaskState::Cancelled)].into());
+        let review = create_review(
+            files.iter().map(|(f, groups)| (f, groups.as_slice())),
+            &week,
+            &sections,
+            &statuses,
+        )
+        .unwrap();
+        assert_eq!(
+            review,
+            "\
+# Review 2026-W42
+
+2026-10-12 to 2026-10-18
+
+## Completed
+
+### 2026-10-13
+
+- [x] outline
+- [x] buy milk
+
+### 2026-10-14
+
+- [X] write RFC
+
+## Open
+
+### Weekly
+
+- [/] review PR
+"
+        );
+    }
+}
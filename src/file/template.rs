@@ -0,0 +1,242 @@
+use super::{render_doc, sort_list, CarryOptions};
+use crate::error::Result;
+use crate::todo::format_date;
+use chrono::NaiveDate;
+use comrak::nodes::{Ast, AstNode, LineColumn, NodeValue};
+use comrak::Arena;
+use indexmap::IndexMap;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Line standing for the carried tasks of a section, e.g. `{{carried:Daily}}`,
+/// along with the blank line before it
+static CARRIED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(^[ \t]*\n)?^[ \t]*\{\{carried:([^}]+)\}\}[ \t]*\n?")
+        .expect("carried placeholder regex is not parsable")
+});
+
+/// Obsidian link to the notes file of `date`, by its name without extension
+fn file_link(date: &NaiveDate, file_pattern: &str) -> String {
+    format_date(date, file_pattern)
+        .and_then(|path| {
+            Path::new(&path)
+                .file_stem()
+                .map(|stem| format!("[[{}]]", stem.to_string_lossy()))
+        })
+        .unwrap_or_default()
+}
+
+/// Markdown of the carried `nodes` of a section
+fn render_nodes<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    nodes: &[&'a AstNode<'a>],
+    sort_by_priority: bool,
+) -> Result<String> {
+    let doc = arena.alloc(AstNode::new(
+        Ast::new(NodeValue::Document, LineColumn { line: 0, column: 0 }).into(),
+    ));
+    for node in nodes {
+        if sort_by_priority && matches!(node.data.borrow().value, NodeValue::List(_)) {
+            sort_list(node);
+        }
+        doc.append(node);
+    }
+    Ok(render_doc(doc)?.trim_end().to_string())
+}
+
+/// Line index just after the heading of `section` in `lines`, if any
+fn section_end(lines: &[&str], section: &str) -> Option<usize> {
+    lines
+        .iter()
+        .position(|line| {
+            let text = line.trim_start_matches('#');
+            line.starts_with("##") && text.starts_with([' ', '\t']) && text.trim() == section
+        })
+        .map(|i| i + 1)
+}
+
+/// Fill in `template` for a new notes file for `date` titled `title`,
+/// carried over from the file of `from` if there is one.
+///
+/// `{{title}}`, `{{date}}`, `{{weekday}}` and `{{yesterday_link}}` are
+/// replaced wherever they appear, a line with only `{{carried:Section}}`
+/// is replaced by the carried tasks of that section. Sections without such a
+/// line get their tasks under their heading in the template, or at the end
+/// of the file under a new heading when the template does not have one.
+pub(super) fn fill_template<'a>(
+    arena: &'a Arena<AstNode<'a>>,
+    template: &str,
+    title: &str,
+    date: &NaiveDate,
+    from: Option<&NaiveDate>,
+    sections: IndexMap<String, Option<Vec<&'a AstNode<'a>>>>,
+    options: &CarryOptions,
+) -> Result<String> {
+    let text = template
+        .replace("{{title}}", title)
+        .replace("{{date}}", &date.format("%Y-%m-%d").to_string())
+        .replace("{{weekday}}", &date.format("%A").to_string())
+        .replace(
+            "{{yesterday_link}}",
+            &from
+                .map(|from| file_link(from, &options.file_pattern))
+                .unwrap_or_default(),
+        );
+
+    let mut carried = IndexMap::new();
+    for (section, nodes) in sections {
+        let nodes = nodes.unwrap_or_default();
+        carried.insert(
+            section,
+            render_nodes(arena, &nodes, options.sort_by_priority)?,
+        );
+    }
+
+    let mut placed = Vec::new();
+    let text = CARRIED_RE.replace_all(&text, |caps: &regex::Captures| {
+        let section = caps[2].trim();
+        placed.push(section.to_string());
+        match carried.get(section) {
+            // no blank lines are left behind for a section without tasks
+            Some(content) if !content.is_empty() => {
+                let blank = if caps.get(1).is_some() { "\n" } else { "" };
+                format!("{}{}\n", blank, content)
+            }
+            _ => String::new(),
+        }
+    });
+
+    let mut lines: Vec<&str> = text.lines().collect();
+    let mut appended = String::new();
+    for (section, content) in &carried {
+        if placed.contains(section) {
+            continue;
+        }
+        match section_end(&lines, section) {
+            Some(_) if content.is_empty() => continue,
+            Some(i) => {
+                lines.insert(i, content);
+                lines.insert(i, "");
+            }
+            None => {
+                appended.push_str(&format!("\n## {}\n", section));
+                if !content.is_empty() {
+                    appended.push_str(&format!("\n{}\n", content));
+                }
+            }
+        }
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output.push_str(&appended);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::{carry_over_merged, carry_over_with_report, empty_file};
+
+    #[test]
+    fn test_fill_template() {
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let contents = "\
+# Today's tasks 2024-01-01
+
+## Daily
+
+- [ ] write docs
+- [x] done
+
+## Weekly
+
+- [ ] review PR
+
+## Monthly
+
+- [ ] taxes
+
+## Journal
+
+yesterday was fine
+";
+        let template = "\
+# {{title}}
+
+{{weekday}}, back to {{yesterday_link}}
+
+## Journal
+
+What went well?
+
+## Daily
+
+{{carried:Daily}}
+
+## Weekly
+";
+        let mut options = CarryOptions::new(&[
+            "Daily".to_string(),
+            "Weekly".to_string(),
+            "Monthly".to_string(),
+        ]);
+        options.title_format = "%Y-%m-%d %a".into();
+        options.file_pattern = "daily/%Y-%m-%d.md".into();
+        options.template = Some(template.into());
+
+        let (content, _) = carry_over_with_report(contents, &from, &date, &options).unwrap();
+        assert_eq!(
+            content,
+            "\
+# 2024-01-02 Tue
+
+Tuesday, back to [[2024-01-01]]
+
+## Journal
+
+What went well?
+
+## Daily
+
+- [ ] write docs
+
+## Weekly
+
+- [ ] review PR
+
+## Monthly
+
+- [ ] taxes
+"
+        );
+
+        // a title recognized by the title format gets the new date and keeps
+        // what follows it
+        let next = NaiveDate::from_ymd_opt(2024, 1, 3).unwrap();
+        let titled = content.replace("# 2024-01-02 Tue\n", "# 2024-01-02 Tue, offsite\n");
+        let (again, _) = carry_over_with_report(&titled, &date, &next, &options).unwrap();
+        assert!(
+            again.starts_with("# 2024-01-03 Wed, offsite\n\nWednesday, back to [[2024-01-02]]\n")
+        );
+
+        let (merged, _) = carry_over_merged(
+            &[(date, content.clone()), (from, contents.into())],
+            &date,
+            &options,
+        )
+        .unwrap();
+        assert!(merged.starts_with("# 2024-01-02 Tue\n\n<!-- carried from 2024-01-02 -->\n\n"));
+
+        let empty = empty_file(&date, &options).unwrap();
+        assert!(empty.contains("Tuesday, back to \n"));
+        assert!(empty.ends_with("## Daily\n\n## Weekly\n\n## Monthly\n"));
+        options.template = None;
+        assert_eq!(
+            empty_file(&date, &options).unwrap(),
+            "# 2024-01-02 Tue\n\n## Daily\n\n## Weekly\n\n## Monthly\n"
+        );
+    }
+}
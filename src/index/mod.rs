@@ -0,0 +1,208 @@
+use crate::error::{Error, Result};
+use crate::file::{find_task_groups, load_file, parse_todo_file, section_names};
+use crate::todo::{File as TodoFile, Task, TaskGroup};
+use comrak::Arena;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, read_to_string};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Name of the index file in the notes directory
+pub const INDEX_FILE: &str = ".rusty-tasks-index.json";
+
+/// Version of the index format, an index with another version is rebuilt
+const INDEX_VERSION: u32 = 2;
+
+/// Tasks of a notes file along with what the file looked like when they were
+/// parsed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    pub file: TodoFile,
+    modified: SystemTime,
+    size: u64,
+    /// every section of the file, including completed tasks
+    pub sections: Vec<TaskGroup>,
+}
+
+/// Parsed tasks of every notes file, kept in the notes directory so they only
+/// need to be parsed again when a file changes
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Index {
+    version: u32,
+    /// entries by path relative to the notes directory
+    files: BTreeMap<PathBuf, IndexEntry>,
+}
+
+impl Index {
+    /// Load the index of `data_dir`, an index that is missing or can not be
+    /// read is treated as empty
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(INDEX_FILE);
+        let Ok(contents) = read_to_string(&path) else {
+            return Index::default();
+        };
+        match serde_json::from_str::<Index>(&contents) {
+            Ok(index) if index.version == INDEX_VERSION => index,
+            Ok(_) => {
+                log::info!("rebuilding index of an older version");
+                Index::default()
+            }
+            Err(e) => {
+                log::warn!("rebuilding unreadable index {}: {}", path.display(), e);
+                Index::default()
+            }
+        }
+    }
+
+    /// Parse the `files` of `data_dir` that changed since they were indexed
+    /// and drop the ones that no longer exist. Returns whether the index
+    /// changed.
+    pub fn update(&mut self, data_dir: &Path, files: &[TodoFile]) -> Result<bool> {
+        let mut changed = self.version != INDEX_VERSION;
+        self.version = INDEX_VERSION;
+
+        let mut files_by_key = BTreeMap::new();
+        for todo_file in files {
+            let key = todo_file
+                .file
+                .strip_prefix(data_dir)
+                .unwrap_or(&todo_file.file)
+                .to_path_buf();
+            files_by_key.insert(key, todo_file);
+        }
+        let before = self.files.len();
+        self.files.retain(|key, _| files_by_key.contains_key(key));
+        changed |= self.files.len() != before;
+
+        for (key, todo_file) in files_by_key {
+            let metadata =
+                fs::metadata(&todo_file.file).map_err(|e| Error::io(&todo_file.file, e))?;
+            let modified = metadata
+                .modified()
+                .map_err(|e| Error::io(&todo_file.file, e))?;
+            let size = metadata.len();
+            if let Some(entry) = self.files.get_mut(&key) {
+                if entry.modified == modified && entry.size == size {
+                    // the notes directory may have moved
+                    entry.file = todo_file.clone();
+                    continue;
+                }
+            }
+
+            log::debug!("indexing {}", todo_file.file.display());
+            let contents = load_file(todo_file)?;
+            let arena = Arena::new();
+            let root = parse_todo_file(&contents, &arena);
+            let mut seen = HashSet::new();
+            let mut names = section_names(root);
+            names.retain(|name| seen.insert(name.clone()));
+            self.files.insert(
+                key,
+                IndexEntry {
+                    file: todo_file.clone(),
+                    modified,
+                    size,
+                    sections: find_task_groups(root, &names),
+                },
+            );
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    /// Write the index to `data_dir`
+    pub fn save(&self, data_dir: &Path) -> Result<()> {
+        let path = data_dir.join(INDEX_FILE);
+        let contents = serde_json::to_string(self).map_err(Error::Serialize)?;
+        fs::write(&path, contents).map_err(|e| Error::io(&path, e))
+    }
+
+    /// Indexed files ordered by date
+    pub fn entries(&self) -> Vec<&IndexEntry> {
+        let mut entries: Vec<&IndexEntry> = self.files.values().collect();
+        entries.sort_by_key(|entry| entry.file.date);
+        entries
+    }
+
+    /// IDs of every task and subtask in the indexed files
+    pub fn ids(&self) -> HashSet<String> {
+        fn collect(tasks: &[Task], ids: &mut HashSet<String>) {
+            for task in tasks {
+                ids.extend(task.id.clone());
+                collect(task.subtasks.as_deref().unwrap_or_default(), ids);
+            }
+        }
+        let mut ids = HashSet::new();
+        for entry in self.files.values() {
+            for group in &entry.sections {
+                collect(&group.tasks, &mut ids);
+            }
+        }
+        ids
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+    use std::env::temp_dir;
+
+    #[test]
+    fn test_index() {
+        let dir = temp_dir().join(format!("rusty-tasks-index-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let write = |name: &str, contents: &str| {
+            let path = dir.join(name);
+            fs::write(&path, contents).unwrap();
+            TodoFile::try_from(path).unwrap()
+        };
+        let first = write(
+            "2024-01-02.md",
+            "# Today's tasks 2024-01-02\n\n## Daily\n\n- [ ] task\n\n## Notes\n\n- [x] note\n",
+        );
+        let second = write("2024-01-01.md", "# Today's tasks 2024-01-01\n\n## Daily\n");
+
+        let mut index = Index::load(&dir);
+        assert_eq!(index, Index::default());
+        assert!(index
+            .update(&dir, &[first.clone(), second.clone()])
+            .unwrap());
+        let entries = index.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].file, second);
+        assert_eq!(entries[1].sections.len(), 2);
+        assert_eq!(entries[1].sections[0].tasks[0].text, "task");
+        assert_eq!(entries[1].sections[0].tasks[0].line, Some(5));
+        assert_eq!(entries[1].sections[1].name, "Notes");
+
+        index.save(&dir).unwrap();
+        let mut index = Index::load(&dir);
+        assert!(!index
+            .update(&dir, &[first.clone(), second.clone()])
+            .unwrap());
+
+        // changed files are parsed again and removed files are dropped
+        let first = write(
+            "2024-01-02.md",
+            "# Today's tasks 2024-01-02\n\n## Daily\n\n- [ ] task\n- [ ] another task ^ab12\n  - [ ] subtask ^cd34\n",
+        );
+        assert!(index.update(&dir, &[first]).unwrap());
+        let entries = index.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].sections[0].tasks.len(), 2);
+        assert_eq!(
+            index.ids(),
+            HashSet::from(["ab12".to_string(), "cd34".to_string()])
+        );
+        assert_eq!(
+            entries[0].file.date,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+
+        fs::write(dir.join(INDEX_FILE), "not json").unwrap();
+        assert_eq!(Index::load(&dir), Index::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
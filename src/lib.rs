@@ -0,0 +1,26 @@
+//! Plain text task management built on daily markdown notes.
+//!
+//! Notes files are named after their date (`YYYY-MM-DD.md`) and contain
+//! sections of tasks. Unfinished tasks are carried over into each new day's
+//! file.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//!
+//! let yesterday = "# Today's tasks 2024-01-01\n\n## Daily\n\n- [ ] write docs\n- [x] fix bug\n";
+//! let today = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+//! let sections = vec!["Daily".to_string()];
+//!
+//! let new_file = rusty_tasks::carry_over(yesterday, &today, &sections).unwrap();
+//! assert_eq!(new_file, "# Today's tasks 2024-01-02\n\n## Daily\n\n- [ ] write docs\n");
+//! ```
+pub mod config;
+pub mod error;
+pub mod file;
+pub mod index;
+pub mod output;
+pub mod stats;
+pub mod todo;
+
+pub use error::{Error, Result};
+pub use file::{carry_over, parse_todo_file, render_doc};
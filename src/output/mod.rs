@@ -0,0 +1,183 @@
+use crate::error::{Error, Result};
+use crate::todo::{File as TodoFile, Lineage, Task, TaskGroup};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Version of the JSON output schema, bumped on incompatible changes
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON document tagged with the schema version
+#[derive(Serialize, Debug)]
+pub struct Versioned<T: Serialize> {
+    pub version: u32,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+/// Notes files, as output by `list`
+#[derive(Serialize, Debug)]
+pub struct FileList {
+    pub files: Vec<TodoFile>,
+}
+
+/// Tasks of a notes file, as output by `show`
+#[derive(Serialize, Debug)]
+pub struct FileTasks {
+    pub file: TodoFile,
+    pub sections: Vec<TaskGroup>,
+}
+
+/// A task found by `search`
+#[derive(Serialize, Debug)]
+pub struct SearchMatch {
+    pub file: TodoFile,
+    /// 1 based line of the task in the file
+    pub line: usize,
+    pub section: String,
+    /// position of the task in the section as passed to `done`, e.g. `3.1`
+    pub position: String,
+    pub task: Task,
+}
+
+/// Tasks found by `search`
+#[derive(Serialize, Debug)]
+pub struct SearchResults {
+    pub matches: Vec<SearchMatch>,
+}
+
+/// Days a task appeared on, as output by `history`
+#[derive(Serialize, Debug)]
+pub struct TaskHistory {
+    pub lineages: Vec<Lineage>,
+}
+
+/// Number of tasks with each tag and context, as output by `list --count-tags`
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub struct TagCounts {
+    pub tags: BTreeMap<String, usize>,
+    pub contexts: BTreeMap<String, usize>,
+}
+
+impl TagCounts {
+    /// Count the tags and contexts of `task` and its subtasks, ignoring case
+    pub fn add(&mut self, task: &Task) {
+        self.add_own(task);
+        for subtask in task.subtasks.iter().flatten() {
+            self.add(subtask);
+        }
+    }
+
+    /// Count the tags and contexts of `task` but not of its subtasks
+    pub fn add_own(&mut self, task: &Task) {
+        for tag in &task.tags {
+            *self.tags.entry(tag.to_lowercase()).or_default() += 1;
+        }
+        for context in &task.contexts {
+            *self.contexts.entry(context.to_lowercase()).or_default() += 1;
+        }
+    }
+}
+
+/// Serialize `data` as pretty printed JSON tagged with the schema version
+pub fn to_json<T: Serialize>(data: T) -> Result<String> {
+    serde_json::to_string_pretty(&Versioned {
+        version: SCHEMA_VERSION,
+        data,
+    })
+    .map_err(Error::Serialize)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::todo::{Status, Task};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_json() {
+        let file = TodoFile::try_from(PathBuf::from("/notes/2024-01-02.md")).unwrap();
+        let json = to_json(FileList {
+            files: vec![file.clone()],
+        })
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "files": [{"path": "/notes/2024-01-02.md", "date": "2024-01-02"}]
+            })
+        );
+
+        let task = |status, text: &str, subtasks| Task {
+            status,
+            text: text.into(),
+            subtasks,
+            ..Default::default()
+        };
+        let json = to_json(FileTasks {
+            file,
+            sections: vec![TaskGroup {
+                name: "Daily".into(),
+                tasks: vec![
+                    task(
+                        Status::Todo('/'),
+                        "in progress",
+                        Some(vec![task(Status::Done('x'), "done", None)]),
+                    ),
+                    task(Status::Empty, "todo", None),
+                ],
+                level: 2,
+            }],
+        })
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "file": {"path": "/notes/2024-01-02.md", "date": "2024-01-02"},
+                "sections": [{
+                    "name": "Daily",
+                    "level": 2,
+                    "tasks": [
+                        {
+                            "status": {"state": "todo", "char": "/"},
+                            "text": "in progress",
+                            "subtasks": [{
+                                "status": {"state": "done", "char": "x"},
+                                "text": "done",
+                                "subtasks": null,
+                                "dates": {}
+                            }],
+                            "dates": {}
+                        },
+                        {
+                            "status": {"state": "empty"},
+                            "text": "todo",
+                            "subtasks": null,
+                            "dates": {}
+                        }
+                    ]
+                }]
+            })
+        );
+
+        let mut counts = TagCounts::default();
+        counts.add(&Task {
+            tags: vec!["Work".into()],
+            contexts: vec!["office".into()],
+            subtasks: Some(vec![Task {
+                tags: vec!["work".into(), "docs".into()],
+                ..Default::default()
+            }]),
+            ..Default::default()
+        });
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&to_json(counts).unwrap()).unwrap(),
+            serde_json::json!({
+                "version": 1,
+                "tags": {"docs": 1, "work": 2},
+                "contexts": {"office": 1}
+            })
+        );
+    }
+}
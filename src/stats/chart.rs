@@ -0,0 +1,62 @@
+/// Characters used to draw charts, from empty to full
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyphs {
+    levels: &'static [char],
+    bar: char,
+}
+
+impl Glyphs {
+    /// block elements, needs a terminal font that has them
+    pub const UNICODE: Glyphs = Glyphs {
+        levels: &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+        bar: '█',
+    };
+    /// plain ASCII for terminals without block elements
+    pub const ASCII: Glyphs = Glyphs {
+        levels: &['_', '.', '-', '=', '+', '*', '#'],
+        bar: '#',
+    };
+}
+
+/// One character per value, as tall as the value relative to the largest.
+/// Zero is drawn as the lowest level so gaps stay visible.
+pub fn sparkline(values: &[usize], glyphs: Glyphs) -> String {
+    let max = values.iter().copied().max().unwrap_or_default();
+    let top = glyphs.levels.len() - 1;
+    values
+        .iter()
+        .map(|value| match max {
+            0 => glyphs.levels[0],
+            _ => glyphs.levels[(value * top).div_ceil(max)],
+        })
+        .collect()
+}
+
+/// Horizontal bar of `value` out of `max`, at most `width` characters long.
+/// A value above zero always gets at least one character.
+pub fn bar(value: usize, max: usize, width: usize, glyphs: Glyphs) -> String {
+    let len = match max {
+        0 => 0,
+        _ => (value * width).div_ceil(max).min(width),
+    };
+    glyphs.bar.to_string().repeat(len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_charts() {
+        assert_eq!(sparkline(&[0, 1, 4, 7, 3], Glyphs::UNICODE), "▁▂▅█▄");
+        assert_eq!(sparkline(&[0, 0], Glyphs::UNICODE), "▁▁");
+        assert_eq!(sparkline(&[], Glyphs::UNICODE), "");
+        assert_eq!(sparkline(&[0, 3, 6], Glyphs::ASCII), "_=#");
+
+        assert_eq!(bar(5, 10, 20, Glyphs::UNICODE), "██████████");
+        assert_eq!(bar(1, 100, 20, Glyphs::ASCII), "#");
+        assert_eq!(bar(0, 10, 20, Glyphs::ASCII), "");
+        assert_eq!(bar(3, 0, 20, Glyphs::ASCII), "");
+        assert_eq!(bar(10, 10, 4, Glyphs::ASCII), "####");
+    }
+}
@@ -0,0 +1,346 @@
+use crate::todo::{task_key, File as TodoFile, StatusMap, Task, TaskGroup, TaskState};
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::HashMap;
+
+mod chart;
+
+pub use chart::{bar, sparkline, Glyphs};
+
+/// Tasks of one day's notes file
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DayStats {
+    pub date: NaiveDate,
+    /// tasks marked as done in the file
+    pub completed: usize,
+    /// unfinished tasks in each section, in the order of `Stats::sections`
+    pub open: Vec<usize>,
+}
+
+/// Tasks seen in one section and how many of them were completed
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SectionStats {
+    pub name: String,
+    /// distinct tasks seen in the section
+    pub tasks: usize,
+    pub completed: usize,
+    /// completed tasks out of all tasks, between 0 and 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_rate: Option<f64>,
+}
+
+/// Longest run of consecutive days with a notes file
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub days: usize,
+}
+
+/// Unfinished task of the newest file that was first seen the longest ago
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct OldestTask {
+    pub section: String,
+    pub first_seen: NaiveDate,
+    /// days between `first_seen` and the newest file
+    pub age: i64,
+    pub task: Task,
+}
+
+/// Statistics over the notes files, as output by `stats`
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Stats {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDate>,
+    /// every day with a notes file, oldest first
+    pub days: Vec<DayStats>,
+    /// times a completed task was carried over on average before it was done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_carry_over: Option<f64>,
+    pub sections: Vec<SectionStats>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_streak: Option<Streak>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_open: Option<OldestTask>,
+}
+
+/// A task followed through the files by its key
+struct Tracked {
+    section: usize,
+    first_seen: NaiveDate,
+    last_seen: NaiveDate,
+    /// files the task appeared in
+    appearances: usize,
+    finished: bool,
+    task: Task,
+}
+
+impl Stats {
+    /// Compute statistics of the top level tasks in `sections` of `files`,
+    /// given oldest first, leaving out files before `since`.
+    ///
+    /// Tasks are followed from file to file by their ID or their text without
+    /// dates, like `todo::task_history` does for exact matches.
+    pub fn compute<'a, I>(
+        files: I,
+        sections: &[String],
+        statuses: &StatusMap,
+        since: Option<NaiveDate>,
+    ) -> Self
+    where
+        I: IntoIterator<Item = (&'a TodoFile, &'a [TaskGroup])>,
+    {
+        let mut days: Vec<DayStats> = Vec::new();
+        let mut section_stats: Vec<SectionStats> = sections
+            .iter()
+            .map(|name| SectionStats {
+                name: name.clone(),
+                tasks: 0,
+                completed: 0,
+                completion_rate: None,
+            })
+            .collect();
+        let mut tracked: HashMap<String, Tracked> = HashMap::new();
+        let mut carry_overs = Vec::new();
+
+        for (file, groups) in files {
+            if since.is_some_and(|since| file.date < since) {
+                continue;
+            }
+            let mut day = DayStats {
+                date: file.date,
+                completed: 0,
+                open: vec![0; sections.len()],
+            };
+            for group in groups {
+                let Some(section) = sections.iter().position(|name| *name == group.name) else {
+                    continue;
+                };
+                for task in &group.tasks {
+                    let state = statuses.status_state(&task.status);
+                    let key = task_key(task);
+                    let first_seen = task
+                        .dates
+                        .created
+                        .map_or(file.date, |created| created.min(file.date));
+                    let entry = tracked.entry(key).or_insert_with(|| Tracked {
+                        section,
+                        first_seen,
+                        last_seen: file.date,
+                        appearances: 0,
+                        finished: false,
+                        task: task.clone(),
+                    });
+                    // a finished task showing up unfinished again is a new
+                    // one, like a renewed recurring task
+                    if entry.finished && !state.is_finished() {
+                        *entry = Tracked {
+                            section,
+                            first_seen: file.date,
+                            last_seen: file.date,
+                            appearances: 0,
+                            finished: false,
+                            task: task.clone(),
+                        };
+                    }
+                    if (entry.appearances > 0 && entry.last_seen == file.date) || entry.finished {
+                        // the same task twice in one file or a finished task
+                        // that was not dropped
+                        continue;
+                    }
+                    if entry.appearances == 0 {
+                        section_stats[section].tasks += 1;
+                    }
+                    entry.appearances += 1;
+                    entry.section = section;
+                    entry.last_seen = file.date;
+                    entry.task = task.clone();
+                    match state {
+                        TaskState::Done => {
+                            entry.finished = true;
+                            carry_overs.push(entry.appearances - 1);
+                            section_stats[section].completed += 1;
+                            day.completed += 1;
+                        }
+                        TaskState::Cancelled => entry.finished = true,
+                        _ => day.open[section] += 1,
+                    }
+                }
+            }
+            days.push(day);
+        }
+
+        for section in section_stats.iter_mut().filter(|s| s.tasks > 0) {
+            section.completion_rate = Some(section.completed as f64 / section.tasks as f64);
+        }
+        let average_carry_over = (!carry_overs.is_empty())
+            .then(|| carry_overs.iter().sum::<usize>() as f64 / carry_overs.len() as f64);
+        let oldest_open = days.last().and_then(|newest| {
+            tracked
+                .values()
+                .filter(|t| !t.finished && t.last_seen == newest.date)
+                .min_by_key(|t| (t.first_seen, t.section))
+                .map(|t| OldestTask {
+                    section: sections[t.section].clone(),
+                    first_seen: t.first_seen,
+                    age: (newest.date - t.first_seen).num_days(),
+                    task: t.task.clone(),
+                })
+        });
+
+        Stats {
+            since,
+            longest_streak: longest_streak(days.iter().map(|day| day.date)),
+            days,
+            average_carry_over,
+            sections: section_stats,
+            oldest_open,
+        }
+    }
+
+    /// Total number of completed tasks
+    pub fn completed(&self) -> usize {
+        self.days.iter().map(|day| day.completed).sum()
+    }
+}
+
+/// Longest run of consecutive `dates`, given in order
+fn longest_streak(dates: impl Iterator<Item = NaiveDate>) -> Option<Streak> {
+    let mut longest: Option<Streak> = None;
+    let mut current: Option<Streak> = None;
+    for date in dates {
+        current = match current {
+            Some(streak) if streak.end.succ_opt() == Some(date) => Some(Streak {
+                end: date,
+                days: streak.days + 1,
+                ..streak
+            }),
+            Some(streak) if streak.end == date => Some(streak),
+            _ => Some(Streak {
+                start: date,
+                end: date,
+                days: 1,
+            }),
+        };
+        if longest.as_ref().map_or(0, |s| s.days) < current.as_ref().map_or(0, |s| s.days) {
+            longest = current.clone();
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::todo::{Status, TaskDates};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_stats() {
+        let file = |d: &str| TodoFile::try_from(PathBuf::from(format!("/notes/{}.md", d))).unwrap();
+        let task = |status, text: &str| Task {
+            status,
+            text: text.into(),
+            dates: TaskDates::parse(text),
+            ..Default::default()
+        };
+        let group = |name: &str, tasks| TaskGroup {
+            name: name.into(),
+            tasks,
+            level: 2,
+        };
+        let files = [
+            (
+                file("2024-01-01"),
+                vec![
+                    group(
+                        "Daily",
+                        vec![
+                            task(Status::Empty, "write RFC"),
+                            task(Status::Done('x'), "buy milk"),
+                        ],
+                    ),
+                    group("Notes", vec![task(Status::Done('x'), "not counted")]),
+                ],
+            ),
+            (
+                file("2024-01-02"),
+                vec![group(
+                    "Daily",
+                    vec![
+                        task(Status::Empty, "write RFC ➕ 2024-01-01"),
+                        task(Status::Empty, "review PR"),
+                        task(Status::Todo('-'), "call bob"),
+                    ],
+                )],
+            ),
+            (
+                file("2024-01-03"),
+                vec![
+                    group("Daily", vec![task(Status::Done('x'), "write RFC")]),
+                    group("Weekly", vec![task(Status::Empty, "review PR")]),
+                ],
+            ),
+            (
+                file("2024-01-05"),
+                vec![group(
+                    "Weekly",
+                    vec![task(Status::Todo('/'), "review PR ➕ 2023-12-30")],
+                )],
+            ),
+        ];
+        let sections = vec!["Daily".to_string(), "Weekly".to_string()];
+        let statuses = StatusMap::new(HashMap::from([('-', TaskState::Cancelled)]));
+        let files = || files.iter().map(|(f, groups)| (f, groups.as_slice()));
+
+        let stats = Stats::compute(files(), &sections, &statuses, None);
+        assert_eq!(
+            stats.days.iter().map(|d| d.completed).collect::<Vec<_>>(),
+            vec![1, 0, 1, 0]
+        );
+        assert_eq!(stats.days[1].open, vec![2, 0]);
+        assert_eq!(stats.days[2].open, vec![0, 1]);
+        assert_eq!(stats.completed(), 2);
+        // buy milk was done right away, write RFC after being carried twice
+        assert_eq!(stats.average_carry_over, Some(1.0));
+        assert_eq!(stats.sections[0].tasks, 4);
+        assert_eq!(stats.sections[0].completed, 2);
+        assert_eq!(stats.sections[0].completion_rate, Some(0.5));
+        assert_eq!(stats.sections[1].tasks, 0);
+        assert_eq!(stats.sections[1].completion_rate, None);
+        assert_eq!(
+            stats.longest_streak,
+            Some(Streak {
+                start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                end: NaiveDate::from_ymd_opt(2024, 1, 3).unwrap(),
+                days: 3,
+            })
+        );
+        let oldest = stats.oldest_open.unwrap();
+        assert_eq!(oldest.section, "Weekly");
+        assert_eq!(
+            oldest.first_seen,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+        assert_eq!(oldest.age, 3);
+
+        let stats = Stats::compute(
+            files(),
+            &sections,
+            &statuses,
+            NaiveDate::from_ymd_opt(2024, 1, 3),
+        );
+        assert_eq!(stats.days.len(), 2);
+        assert_eq!(stats.average_carry_over, Some(0.0));
+        assert_eq!(stats.longest_streak.unwrap().days, 1);
+        assert_eq!(
+            stats.oldest_open.unwrap().first_seen,
+            NaiveDate::from_ymd_opt(2024, 1, 3).unwrap()
+        );
+
+        let stats = Stats::compute(files().take(0), &sections, &statuses, None);
+        assert!(stats.days.is_empty());
+        assert_eq!(stats.longest_streak, None);
+        assert_eq!(stats.oldest_open, None);
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{NaiveDate, TimeDelta};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// Marker for the date a task was first seen, as used by Obsidian Tasks
+pub const CREATED_MARKER: &str = "➕";
+/// Marker for the date a task is due, as used by Obsidian Tasks
+pub const DUE_MARKER: &str = "📅";
+const DATE_FMT: &str = "%Y-%m-%d";
+
+/// Regex for a date written as `<marker> YYYY-MM-DD` (Obsidian Tasks) or
+/// `<key>:YYYY-MM-DD` (todo.txt)
+fn date_re(markers: &str, key: &str) -> Regex {
+    Regex::new(&format!(
+        r"(?:(?:{})\s*|\b{}:)(\d{{4}}-\d{{2}}-\d{{2}})",
+        markers, key
+    ))
+    .expect("date regex is not parsable")
+}
+
+static CREATED_RE: LazyLock<Regex> = LazyLock::new(|| date_re(CREATED_MARKER, "created"));
+static DUE_RE: LazyLock<Regex> = LazyLock::new(|| date_re("📅|📆|🗓\u{FE0F}?", "due"));
+static SCHEDULED_RE: LazyLock<Regex> = LazyLock::new(|| date_re("⏳|⌛", "scheduled"));
+static START_RE: LazyLock<Regex> = LazyLock::new(|| date_re("🛫", "start"));
+static COMPLETED_RE: LazyLock<Regex> = LazyLock::new(|| date_re("✅", "done"));
+/// created and completion dates along with the spaces in front of them
+static STRIP_COMPLETION_RES: LazyLock<[Regex; 2]> = LazyLock::new(|| {
+    [&*CREATED_RE, &*COMPLETED_RE].map(|re| {
+        Regex::new(&format!("[ \t]*{}", re.as_str())).expect("date regex is not parsable")
+    })
+});
+
+/// Dates recorded in the text of a task, either with an Obsidian Tasks
+/// marker (`📅 2024-01-03`) or as `key:value` (`due:2024-01-03`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskDates {
+    /// day the task was first seen, `➕` or `created:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<NaiveDate>,
+    /// day the task is due, `📅` or `due:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<NaiveDate>,
+    /// day the task is planned to be worked on, `⏳` or `scheduled:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled: Option<NaiveDate>,
+    /// day the task can be started, `🛫` or `start:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<NaiveDate>,
+    /// day the task was completed, `✅` or `done:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<NaiveDate>,
+}
+
+impl TaskDates {
+    pub fn parse(text: &str) -> Self {
+        TaskDates {
+            created: find_date(&CREATED_RE, text),
+            due: find_date(&DUE_RE, text),
+            scheduled: find_date(&SCHEDULED_RE, text),
+            start: find_date(&START_RE, text),
+            completed: find_date(&COMPLETED_RE, text),
+        }
+    }
+}
+
+/// Date captured by the first group of `re`
+fn find_date(re: &Regex, text: &str) -> Option<NaiveDate> {
+    re.captures(text)
+        .and_then(|caps| NaiveDate::parse_from_str(&caps[1], DATE_FMT).ok())
+}
+
+/// `text` with every date matched by `re` replaced by `update(date)`
+fn replace_dates<F>(re: &Regex, text: &str, update: F) -> String
+where
+    F: Fn(NaiveDate) -> Option<NaiveDate>,
+{
+    re.replace_all(text, |caps: &Captures| {
+        let prefix = &caps[0][..caps[0].len() - caps[1].len()];
+        match NaiveDate::parse_from_str(&caps[1], DATE_FMT)
+            .ok()
+            .map(&update)
+        {
+            Some(Some(date)) => format!("{}{}", prefix, date.format(DATE_FMT)),
+            Some(None) => String::new(),
+            None => caps[0].to_string(),
+        }
+    })
+    .into_owned()
+}
+
+/// `text` with its due, scheduled and start dates moved by `days`
+pub fn shift_dates(text: &str, days: i64) -> String {
+    let shift = |date: NaiveDate| date.checked_add_signed(TimeDelta::days(days));
+    [&*DUE_RE, &*SCHEDULED_RE, &*START_RE]
+        .into_iter()
+        .fold(text.to_string(), |text, re| replace_dates(re, &text, shift))
+}
+
+/// `text` without its created and completion dates
+pub fn strip_completion(text: &str) -> String {
+    STRIP_COMPLETION_RES
+        .iter()
+        .fold(text.to_string(), |text, re| {
+            replace_dates(re, &text, |_| None)
+        })
+}
+
+/// `text` without any of its dates
+pub fn strip_dates(text: &str) -> String {
+    [&*DUE_RE, &*SCHEDULED_RE, &*START_RE]
+        .into_iter()
+        .fold(strip_completion(text), |text, re| {
+            replace_dates(re, &text, |_| None)
+        })
+}
+
+/// Text `format` gives `date`, `None` when `format` is not a valid strftime
+/// pattern
+pub fn format_date(date: &NaiveDate, format: &str) -> Option<String> {
+    use std::fmt::Write;
+
+    let mut text = String::new();
+    write!(text, "{}", date.format(format)).ok()?;
+    Some(text)
+}
+
+/// whether `format` writes out a whole date that can be read back, as needed
+/// for titles and file names
+pub fn formats_date(format: &str) -> bool {
+    let date = NaiveDate::from_ymd_opt(2024, 12, 31).expect("a valid date");
+    format_date(&date, format)
+        .is_some_and(|text| NaiveDate::parse_from_str(&text, format) == Ok(date))
+}
+
+/// Text to append to a task to record the day it was first seen
+pub fn created_marker(date: &NaiveDate) -> String {
+    format!(" {} {}", CREATED_MARKER, date.format(DATE_FMT))
+}
+
+/// Text to append to a task to record the day it is due
+pub fn due_marker(date: &NaiveDate) -> String {
+    format!(" {} {}", DUE_MARKER, date.format(DATE_FMT))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_dates() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 3);
+        assert_eq!(TaskDates::parse("write docs ➕ 2024-01-03").created, date);
+        assert_eq!(
+            TaskDates::parse("write docs ➕2024-01-03 more").created,
+            date
+        );
+        assert_eq!(TaskDates::parse("write docs ➕ 2024-13-03").created, None);
+        assert_eq!(TaskDates::parse("write docs 2024-01-03").created, None);
+
+        assert_eq!(TaskDates::parse("report 📅 2024-01-03").due, date);
+        assert_eq!(TaskDates::parse("report due:2024-01-03 #work").due, date);
+        assert_eq!(TaskDates::parse("report overdue:2024-01-03").due, None);
+        assert_eq!(TaskDates::parse("report due: 2024-01-03").due, None);
+
+        let dates = TaskDates::parse("report ⏳ 2024-01-01 start:2024-01-02 ✅ 2024-01-03");
+        assert_eq!(dates.scheduled, NaiveDate::from_ymd_opt(2024, 1, 1));
+        assert_eq!(dates.start, NaiveDate::from_ymd_opt(2024, 1, 2));
+        assert_eq!(dates.completed, date);
+        assert_eq!(dates.due, None);
+        assert_eq!(
+            TaskDates::parse("report scheduled:2024-01-01 🛫 2024-01-02 done:2024-01-03"),
+            dates
+        );
+
+        assert_eq!(
+            shift_dates("report 📅 2024-01-03 start:2024-01-01 ➕ 2023-12-01", 7),
+            "report 📅 2024-01-10 start:2024-01-08 ➕ 2023-12-01"
+        );
+        assert_eq!(
+            strip_completion("report ➕ 2023-12-01 📅 2024-01-03 ✅ 2024-01-03"),
+            "report 📅 2024-01-03"
+        );
+        assert_eq!(strip_completion("report done:2024-01-03"), "report");
+        assert_eq!(
+            strip_dates("report 📅 2024-01-03 #work start:2024-01-01 ✅ 2024-01-03"),
+            "report  #work "
+        );
+
+        let text = format!("write docs{}", created_marker(&date.unwrap()));
+        assert_eq!(text, "write docs ➕ 2024-01-03");
+        assert_eq!(TaskDates::parse(&text).created, date);
+    }
+}
@@ -1,47 +1,73 @@
 use chrono::naive::NaiveDate;
+use serde::{Deserialize, Serialize};
 use std::cmp::min;
 use std::convert::TryFrom;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use super::dates::{format_date, formats_date};
 use crate::file::FileNameParseError;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct File {
+    #[serde(rename = "path")]
     pub file: PathBuf,
     pub date: NaiveDate,
 }
 
-fn try_get_date(file: &PathBuf) -> Result<NaiveDate, FileNameParseError> {
-    let file_name = file
-        .file_name()
-        .ok_or(FileNameParseError::TypeConversionError(
-            "Could not get filename from path: {:?}",
-        ))?
-        .to_str()
-        .ok_or(FileNameParseError::TypeConversionError(
-            "Could not get filename from path: {:?}",
-        ))?;
-
-    NaiveDate::parse_from_str(file_name, "%Y-%m-%d.md")
-        .or_else(|e| Err(FileNameParseError::ParseError(e)))
+/// Pattern of the path of a notes file relative to the notes directory when
+/// none is configured
+pub const DEFAULT_FILE_PATTERN: &str = "%Y-%m-%d.md";
+
+/// whether `pattern` formats every date to a relative path it can be parsed
+/// back from
+pub fn valid_file_pattern(pattern: &str) -> bool {
+    let date = NaiveDate::from_ymd_opt(2024, 12, 31).expect("a valid date");
+    formats_date(pattern)
+        && format_date(&date, pattern).is_some_and(|path| {
+            !path.starts_with('/') && !path.split('/').any(|part| part.is_empty() || part == "..")
+        })
+}
+
+/// Date of `file` according to `pattern`, matched against as many trailing
+/// components of the path as the pattern has (`%Y/%m/%Y-%m-%d.md` against
+/// the file name and its two parent directories)
+fn try_get_date(file: &Path, pattern: &str) -> Result<NaiveDate, FileNameParseError> {
+    let depth = pattern.split('/').count();
+    let mut components = file
+        .components()
+        .rev()
+        .take(depth)
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| FileNameParseError::TypeConversionError(file.to_path_buf()))?;
+    components.reverse();
+
+    NaiveDate::parse_from_str(&components.join("/"), pattern).map_err(|source| {
+        FileNameParseError::ParseError {
+            path: file.to_path_buf(),
+            source,
+        }
+    })
 }
 
 impl TryFrom<PathBuf> for File {
     type Error = FileNameParseError;
 
+    /// Notes file named with the default pattern
     fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-        Ok(Self {
-            date: try_get_date(&path)?,
-            file: path.into(),
-        })
+        File::parse(path, DEFAULT_FILE_PATTERN)
     }
 }
 impl File {
-    pub fn get_closest_files(files: Vec<PathBuf>, target: NaiveDate, n: usize) -> Vec<File> {
-        let mut dated_files = files
-            .into_iter()
-            .filter_map(|file| File::try_from(file).ok())
-            .collect::<Vec<_>>();
+    /// Notes file whose path ends with `pattern`, e.g. `%Y/%m/%Y-%m-%d.md`
+    pub fn parse(path: PathBuf, pattern: &str) -> Result<Self, FileNameParseError> {
+        Ok(Self {
+            date: try_get_date(&path, pattern)?,
+            file: path,
+        })
+    }
+
+    pub fn get_closest_files(mut dated_files: Vec<File>, target: NaiveDate, n: usize) -> Vec<File> {
         dated_files.sort_by_cached_key(|dated_file| (dated_file.date - target).num_days().abs());
 
         let count = min(n, dated_files.len());
@@ -57,7 +83,7 @@ mod test {
 
     #[test]
     fn test_get_closest_date() {
-        let files = vec![
+        let files: Vec<File> = [
             PathBuf::from("./2024-01-01.md"),
             PathBuf::from("./2024-01-02.md"),
             PathBuf::from("./2024-01-03.md"),
@@ -67,7 +93,10 @@ mod test {
             PathBuf::from("./2024-04-02.md"),
             PathBuf::from("./2024-04-03.md"),
             PathBuf::from("./2024-04-04.md"),
-        ];
+        ]
+        .into_iter()
+        .map(|path| File::try_from(path).unwrap())
+        .collect();
 
         let res = File::get_closest_files(
             files.clone(),
@@ -112,5 +141,33 @@ mod test {
         );
         let expected_res = vec![File::try_from(PathBuf::from("./2024-01-01.md")).unwrap()];
         assert_eq!(res, expected_res);
+
+        let nested = PathBuf::from("/vault/2024/01/2024-01-02.md");
+        let file = File::parse(nested.clone(), "%Y/%m/%Y-%m-%d.md").unwrap();
+        assert_eq!(file.date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert!(File::parse(nested.clone(), "%Y/%m/%d/%Y-%m-%d.md").is_err());
+        assert!(File::parse(
+            PathBuf::from("/vault/2024/02/2024-01-02.md"),
+            "%Y/%m/%Y-%m-%d.md"
+        )
+        .is_err());
+        let file = File::parse(
+            PathBuf::from("/vault/daily/2024-01-02-Tue.md"),
+            "daily/%Y-%m-%d-%a.md",
+        )
+        .unwrap();
+        assert_eq!(file.date, NaiveDate::from_ymd_opt(2024, 1, 2).unwrap());
+        assert!(File::parse(
+            PathBuf::from("/vault/2024-01-02-Tue.md"),
+            "daily/%Y-%m-%d-%a.md"
+        )
+        .is_err());
+
+        assert!(valid_file_pattern(DEFAULT_FILE_PATTERN));
+        assert!(valid_file_pattern("daily/%Y-%m-%d-%a.md"));
+        assert!(!valid_file_pattern("%Y-%m.md"));
+        assert!(!valid_file_pattern("%Y-%m-%d-%Q.md"));
+        assert!(!valid_file_pattern("/%Y-%m-%d.md"));
+        assert!(!valid_file_pattern("../%Y-%m-%d.md"));
     }
 }
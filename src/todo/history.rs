@@ -0,0 +1,255 @@
+use super::dates::strip_dates;
+use super::file::File;
+use super::id::id_offset;
+use super::tasks::{Task, TaskGroup};
+use serde::Serialize;
+use similar::TextDiff;
+
+/// How similar the text of a task has to be to the text it had the day before
+/// to be considered the same task, between 0 and 1
+const SIMILARITY: f32 = 0.8;
+
+/// A task as it appeared in one notes file
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Appearance {
+    pub file: File,
+    pub section: String,
+    /// position of the task in the section, e.g. `3.1`
+    pub position: String,
+    pub task: Task,
+}
+
+/// Every appearance of one task across the notes files, oldest first
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Lineage {
+    pub appearances: Vec<Appearance>,
+}
+
+/// Text of a task without its dates and ID, in lowercase and with whitespace
+/// collapsed, so carried tasks still match after being stamped or renewed
+fn normalize(text: &str) -> String {
+    strip_dates(&text[..id_offset(text).unwrap_or(text.len())])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Key identifying a task across files, its ID if it has one or else its
+/// normalized text
+pub(crate) fn task_key(task: &Task) -> String {
+    match &task.id {
+        Some(id) => format!("^{}", id),
+        None => normalize(&task.text),
+    }
+}
+
+/// whether the normalized texts `a` and `b` belong to the same task
+fn same_task(a: &str, b: &str) -> bool {
+    a == b || TextDiff::from_chars(a, b).ratio() >= SIMILARITY
+}
+
+/// Tasks and subtasks of `tasks` along with their dotted position
+fn flatten<'a>(tasks: &'a [Task], parent: &str, out: &mut Vec<(String, &'a Task)>) {
+    for (i, task) in tasks.iter().enumerate() {
+        let position = format!("{}{}", parent, i + 1);
+        out.push((position.clone(), task));
+        if let Some(subtasks) = &task.subtasks {
+            flatten(subtasks, &format!("{}.", position), out);
+        }
+    }
+}
+
+/// Follow the tasks containing `query` (ignoring case) through `files`,
+/// given oldest first with their sections.
+///
+/// A task found in one file is the same as a task in a later file when they
+/// have the same ID, or when either has no ID and their text is identical or
+/// close enough once dates are ignored. So a task can be followed through
+/// carry-overs even if it is edited a little along the way, or completely
+/// once it has an ID.
+pub fn task_history<'a, I>(files: I, query: &str) -> Vec<Lineage>
+where
+    I: IntoIterator<Item = (&'a File, &'a [TaskGroup])>,
+{
+    let query = query.to_lowercase();
+    // lineages along with the normalized text they were last seen with
+    let mut lineages: Vec<(String, Lineage)> = Vec::new();
+
+    for (file, groups) in files {
+        let mut tasks = Vec::new();
+        for group in groups {
+            let mut group_tasks = Vec::new();
+            flatten(&group.tasks, "", &mut group_tasks);
+            tasks.extend(group_tasks.into_iter().map(|(position, task)| {
+                let appearance = Appearance {
+                    file: file.clone(),
+                    section: group.name.clone(),
+                    position,
+                    task: task.clone(),
+                };
+                (normalize(&task.text), Some(appearance))
+            }));
+        }
+
+        // identical text first so an edited task does not take the place of
+        // one that was carried as is, then the most similar text
+        let mut continued = vec![false; lineages.len()];
+        for exact in [true, false] {
+            for (text, appearance) in tasks.iter_mut() {
+                let Some(id) = appearance.as_ref().map(|a| a.task.id.clone()) else {
+                    continue;
+                };
+                let ratio = |i: &usize| {
+                    TextDiff::from_chars(lineages[*i].0.as_str(), text.as_str()).ratio()
+                };
+                // tasks with IDs are the same when their IDs are
+                let same_id = |i: &usize| {
+                    let last = lineages[*i].1.appearances.last();
+                    match (last.and_then(|a| a.task.id.as_ref()), &id) {
+                        (Some(last_id), Some(id)) => Some(last_id == id),
+                        _ => None,
+                    }
+                };
+                let previous = (0..continued.len())
+                    .filter(|i| !continued[*i])
+                    .filter(|i| match same_id(i) {
+                        Some(same) => exact && same,
+                        None if exact => lineages[*i].0 == *text,
+                        None => same_task(&lineages[*i].0, text),
+                    })
+                    .max_by(|a, b| ratio(a).total_cmp(&ratio(b)));
+                if let Some(i) = previous {
+                    continued[i] = true;
+                    lineages[i].0 = text.clone();
+                    lineages[i].1.appearances.extend(appearance.take());
+                }
+            }
+        }
+
+        for (text, appearance) in tasks {
+            if let Some(appearance) =
+                appearance.filter(|a| a.task.text.to_lowercase().contains(&query))
+            {
+                lineages.push((
+                    text,
+                    Lineage {
+                        appearances: vec![appearance],
+                    },
+                ));
+            }
+        }
+    }
+    lineages.into_iter().map(|(_, lineage)| lineage).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::todo::Status;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_task_history() {
+        let file = |d: &str| File::try_from(PathBuf::from(format!("/notes/{}.md", d))).unwrap();
+        let task = |status, text: &str| Task {
+            status,
+            text: text.into(),
+            ..Default::default()
+        };
+        let group = |name: &str, tasks| TaskGroup {
+            name: name.into(),
+            tasks,
+            level: 2,
+        };
+        let files = [
+            (
+                file("2024-01-01"),
+                vec![group(
+                    "Daily",
+                    vec![
+                        task(Status::Empty, "write RFC"),
+                        task(Status::Empty, "buy milk"),
+                    ],
+                )],
+            ),
+            (
+                file("2024-01-02"),
+                vec![group(
+                    "Daily",
+                    vec![
+                        task(Status::Todo('/'), "write RFC ➕ 2024-01-01"),
+                        task(Status::Empty, "write RFC for billing"),
+                    ],
+                )],
+            ),
+            (file("2024-01-03"), vec![]),
+            (
+                file("2024-01-04"),
+                vec![group(
+                    "Weekly",
+                    vec![
+                        task(Status::Done('x'), "write the RFC ➕ 2024-01-01"),
+                        task(Status::Empty, "write RFC for billing ^b1"),
+                    ],
+                )],
+            ),
+        ];
+        let history = task_history(
+            files.iter().map(|(f, groups)| (f, groups.as_slice())),
+            "rfc",
+        );
+
+        assert_eq!(history.len(), 2);
+        let days = |lineage: &Lineage| -> Vec<(String, String)> {
+            lineage
+                .appearances
+                .iter()
+                .map(|a| (a.file.date.to_string(), a.section.clone()))
+                .collect()
+        };
+        assert_eq!(
+            days(&history[0]),
+            vec![
+                ("2024-01-01".into(), "Daily".into()),
+                ("2024-01-02".into(), "Daily".into()),
+                ("2024-01-04".into(), "Weekly".into()),
+            ]
+        );
+        assert_eq!(history[0].appearances[2].task.status, Status::Done('x'));
+        assert_eq!(
+            days(&history[1]),
+            vec![
+                ("2024-01-02".into(), "Daily".into()),
+                ("2024-01-04".into(), "Weekly".into()),
+            ]
+        );
+        assert_eq!(history[1].appearances[0].position, "2");
+
+        // once it has an ID a task can be renamed
+        let mut renamed = files[3].clone();
+        renamed.0 = file("2024-01-05");
+        renamed.1[0].tasks[1] = task(Status::Done('x'), "bill customers ^b1");
+        renamed.1[0].tasks[1].id = Some("b1".into());
+        let mut files = files.to_vec();
+        files[3].1[0].tasks[1].id = Some("b1".into());
+        files.push(renamed);
+        let history = task_history(
+            files.iter().map(|(f, groups)| (f, groups.as_slice())),
+            "billing",
+        );
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].appearances.len(), 3);
+        assert_eq!(history[0].appearances[2].task.text, "bill customers ^b1");
+
+        assert_eq!(
+            normalize("Write  RFC 📅 2024-01-03 ✅ 2024-01-04 ^a1b2"),
+            "write rfc"
+        );
+        assert!(task_history(
+            files.iter().map(|(f, groups)| (f, groups.as_slice())),
+            "milk tea"
+        )
+        .is_empty());
+    }
+}
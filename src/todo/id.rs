@@ -0,0 +1,44 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Obsidian block reference at the end of a task, e.g. `^a1b2`
+static BLOCK_ID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[ \t]+)\^([A-Za-z0-9-]+)[ \t]*$").expect("block id regex is not parsable")
+});
+
+/// ID of a task, written as an Obsidian block reference at the end of its
+/// text (`- [ ] write docs ^a1b2`)
+pub fn parse_id(text: &str) -> Option<String> {
+    BLOCK_ID_RE.captures(text).map(|caps| caps[1].to_string())
+}
+
+/// Byte offset of the block reference at the end of `text`, including the
+/// spaces in front of it. Text appended to a task goes before it, since a
+/// block reference has to end the line.
+pub fn id_offset(text: &str) -> Option<usize> {
+    BLOCK_ID_RE.find(text).map(|m| m.start())
+}
+
+/// Text to append to a task to give it `id`
+pub fn id_marker(id: &str) -> String {
+    format!(" ^{}", id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_id() {
+        assert_eq!(parse_id("write docs ^a1b2"), Some("a1b2".into()));
+        assert_eq!(parse_id("write docs ^a1b2  "), Some("a1b2".into()));
+        assert_eq!(parse_id("write docs ^a1b2 later"), None);
+        assert_eq!(parse_id("2^10 is 1024"), None);
+        assert_eq!(parse_id("write docs"), None);
+
+        assert_eq!(id_offset("write docs ^a1b2"), Some(10));
+        assert_eq!(id_offset("write docs"), None);
+        let text = format!("write docs{}", id_marker("x9"));
+        assert_eq!(parse_id(&text), Some("x9".into()));
+    }
+}
@@ -1,5 +1,23 @@
+mod dates;
 mod file;
+mod history;
+mod id;
+mod priority;
+mod recur;
+mod state;
+mod tags;
 mod tasks;
 
-pub use file::File;
-pub use tasks::{Status, Task, TaskGroup};
+pub use dates::{
+    created_marker, due_marker, format_date, formats_date, shift_dates, strip_completion,
+    TaskDates, CREATED_MARKER, DUE_MARKER,
+};
+pub use file::{valid_file_pattern, File, DEFAULT_FILE_PATTERN};
+pub(crate) use history::task_key;
+pub use history::{task_history, Appearance, Lineage};
+pub use id::{id_marker, id_offset, parse_id};
+pub use priority::Priority;
+pub use recur::{Recurrence, RECUR_MARKER};
+pub use state::{StatusMap, TaskState};
+pub use tags::{parse_contexts, parse_tags, unescape_tags};
+pub use tasks::{Status, Task, TaskError, TaskGroup};
@@ -0,0 +1,86 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+static PRIORITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^\(([A-Z])\)(?:\s|$)|(?:^|\s)(!!!?)(?:\s|$)|(🔺|⏫|🔼|🔽|⏬))")
+        .expect("priority regex is not parsable")
+});
+
+/// How important a task is, written in the task text as an Obsidian Tasks
+/// marker (`⏫`), a todo.txt priority (`(A)`) or as `!!!`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// `⏬`
+    Lowest,
+    /// `🔽`, `(C)` and below
+    Low,
+    /// no priority marker
+    #[default]
+    Normal,
+    /// `🔼`, `(B)` or `!!`
+    Medium,
+    /// `⏫`, `(A)` or `!!!`
+    High,
+    /// `🔺`
+    Highest,
+}
+
+impl Priority {
+    /// Find the first priority marker in the text of a task
+    pub fn parse(text: &str) -> Self {
+        let Some(caps) = PRIORITY_RE.captures(text.trim_start()) else {
+            return Priority::Normal;
+        };
+        let marker = caps
+            .iter()
+            .skip(1)
+            .flatten()
+            .next()
+            .map_or("", |m| m.as_str());
+        match marker {
+            "A" | "!!!" | "⏫" => Priority::High,
+            "B" | "!!" | "🔼" => Priority::Medium,
+            "🔺" => Priority::Highest,
+            "🔽" => Priority::Low,
+            "⏬" => Priority::Lowest,
+            // (C) to (Z)
+            _ => Priority::Low,
+        }
+    }
+
+    pub fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_priority() {
+        assert_eq!(Priority::parse("(A) call mom"), Priority::High);
+        assert_eq!(Priority::parse("(B) call mom"), Priority::Medium);
+        assert_eq!(Priority::parse("(D) call mom"), Priority::Low);
+        assert_eq!(Priority::parse("call mom (A)"), Priority::Normal);
+        assert_eq!(Priority::parse("(a) call mom"), Priority::Normal);
+        assert_eq!(Priority::parse("call mom !!!"), Priority::High);
+        assert_eq!(Priority::parse("!! call mom"), Priority::Medium);
+        assert_eq!(Priority::parse("call mom!!!"), Priority::Normal);
+        assert_eq!(Priority::parse("call mom ! now"), Priority::Normal);
+        assert_eq!(
+            Priority::parse("report 🔺 📅 2024-01-03"),
+            Priority::Highest
+        );
+        assert_eq!(Priority::parse("report ⏫"), Priority::High);
+        assert_eq!(Priority::parse("report 🔼"), Priority::Medium);
+        assert_eq!(Priority::parse("report 🔽"), Priority::Low);
+        assert_eq!(Priority::parse("report ⏬"), Priority::Lowest);
+        assert_eq!(Priority::parse("report"), Priority::Normal);
+
+        assert!(Priority::Highest > Priority::High);
+        assert!(Priority::Normal > Priority::Low);
+    }
+}
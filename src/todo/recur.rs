@@ -0,0 +1,146 @@
+use chrono::{Datelike, Months, NaiveDate, TimeDelta, Weekday};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Marker in front of a recurrence rule, as used by Obsidian Tasks
+pub const RECUR_MARKER: &str = "🔁";
+
+static RECUR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i){}\s*every\s+(?:(\d+)\s+)?([a-z]+?)s?\b(?:\s+on\s+the\s+(\d+)(?:st|nd|rd|th)\b)?",
+        RECUR_MARKER
+    ))
+    .expect("recurrence regex is not parsable")
+});
+
+/// How often a task comes back once it is completed, written in the task
+/// text as `🔁 every monday`, `🔁 every 2 weeks` or
+/// `🔁 every month on the 1st`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recurrence {
+    Days(u32),
+    Weeks(u32),
+    Months { every: u32, day: Option<u32> },
+    Years(u32),
+    Weekday(Weekday),
+}
+
+impl Recurrence {
+    /// Find the recurrence rule in the text of a task
+    pub fn parse(text: &str) -> Option<Self> {
+        let caps = RECUR_RE.captures(text)?;
+        let every = match caps.get(1) {
+            Some(n) => n.as_str().parse().ok().filter(|n| *n > 0)?,
+            None => 1,
+        };
+        let day = match caps.get(3) {
+            Some(day) => Some(day.as_str().parse().ok().filter(|d| (1..=31).contains(d))?),
+            None => None,
+        };
+        let unit = caps[2].to_lowercase();
+
+        match unit.as_str() {
+            "month" => Some(Recurrence::Months { every, day }),
+            _ if day.is_some() => None,
+            "day" => Some(Recurrence::Days(every)),
+            "week" => Some(Recurrence::Weeks(every)),
+            "year" => Some(Recurrence::Years(every)),
+            _ if every == 1 => unit.parse().ok().map(Recurrence::Weekday),
+            _ => None,
+        }
+    }
+
+    /// First day the task is due again after `date`
+    pub fn next(&self, date: &NaiveDate) -> NaiveDate {
+        match *self {
+            Recurrence::Days(n) => *date + TimeDelta::days(n.into()),
+            Recurrence::Weeks(n) => *date + TimeDelta::weeks(n.into()),
+            Recurrence::Months { every, day: None } => add_months(date, every),
+            Recurrence::Months {
+                every,
+                day: Some(day),
+            } => {
+                let this_month = with_day(date, day);
+                if this_month > *date {
+                    this_month
+                } else {
+                    with_day(&add_months(&date.with_day(1).unwrap_or(*date), every), day)
+                }
+            }
+            Recurrence::Years(n) => add_months(date, n.saturating_mul(12)),
+            Recurrence::Weekday(weekday) => {
+                let days = (weekday.num_days_from_monday() + 7
+                    - date.weekday().num_days_from_monday())
+                    % 7;
+                *date + TimeDelta::days(if days == 0 { 7 } else { days.into() })
+            }
+        }
+    }
+}
+
+/// `date` moved by `months`, clamped to the end of shorter months
+fn add_months(date: &NaiveDate, months: u32) -> NaiveDate {
+    date.checked_add_months(Months::new(months))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+/// `day` of the month of `date`, clamped to the last day of the month
+fn with_day(date: &NaiveDate, day: u32) -> NaiveDate {
+    (1..=day)
+        .rev()
+        .find_map(|d| date.with_day(d))
+        .unwrap_or(*date)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_recurrence() {
+        let parse = |text| Recurrence::parse(text);
+        assert_eq!(
+            parse("trash 🔁 every monday"),
+            Some(Recurrence::Weekday(Weekday::Mon))
+        );
+        assert_eq!(
+            parse("trash 🔁 Every Mondays"),
+            Some(Recurrence::Weekday(Weekday::Mon))
+        );
+        assert_eq!(
+            parse("report 🔁every 2 weeks 📅 2024-01-05"),
+            Some(Recurrence::Weeks(2))
+        );
+        assert_eq!(parse("water 🔁 every day"), Some(Recurrence::Days(1)));
+        assert_eq!(
+            parse("rent 🔁 every month on the 1st"),
+            Some(Recurrence::Months {
+                every: 1,
+                day: Some(1)
+            })
+        );
+        assert_eq!(parse("taxes 🔁 every year"), Some(Recurrence::Years(1)));
+        assert_eq!(parse("review every monday"), None);
+        assert_eq!(parse("🔁 every 0 days"), None);
+        assert_eq!(parse("🔁 every fortnight"), None);
+        assert_eq!(parse("🔁 every week on the 3rd"), None);
+        assert_eq!(parse("🔁 every 2 mondays"), None);
+
+        // 2024-01-31 is a Wednesday
+        let date = |m, d| NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        let next = |text, from| parse(text).unwrap().next(&from);
+        assert_eq!(next("🔁 every monday", date(1, 31)), date(2, 5));
+        assert_eq!(next("🔁 every wednesday", date(1, 31)), date(2, 7));
+        assert_eq!(next("🔁 every 2 weeks", date(1, 31)), date(2, 14));
+        assert_eq!(next("🔁 every 3 days", date(1, 31)), date(2, 3));
+        assert_eq!(next("🔁 every month", date(1, 31)), date(2, 29));
+        assert_eq!(next("🔁 every month on the 1st", date(1, 31)), date(2, 1));
+        assert_eq!(next("🔁 every month on the 15th", date(1, 3)), date(1, 15));
+        assert_eq!(next("🔁 every month on the 31st", date(1, 31)), date(2, 29));
+        assert_eq!(next("🔁 every 2 months on the 2nd", date(1, 3)), date(3, 2));
+        assert_eq!(
+            next("🔁 every year", date(1, 31)),
+            date(1, 31) + Months::new(12)
+        );
+    }
+}
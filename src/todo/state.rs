@@ -0,0 +1,109 @@
+use super::tasks::Status;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// What the status character of a task means
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskState {
+    /// not started, carried over
+    #[default]
+    Open,
+    /// being worked on, carried over
+    InProgress,
+    /// put off for later, carried over
+    Deferred,
+    /// completed, dropped on carry-over
+    Done,
+    /// no longer needed, dropped on carry-over
+    Cancelled,
+}
+
+impl TaskState {
+    /// whether the task needs no more work and is not carried over
+    pub fn is_finished(&self) -> bool {
+        matches!(self, TaskState::Done | TaskState::Cancelled)
+    }
+}
+
+impl FromStr for TaskState {
+    type Err = String;
+
+    /// Parse a state by its name in the config, e.g. `in-progress`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_lowercase()))
+            .map_err(|_| format!("unknown task state: {}", s))
+    }
+}
+
+/// Meaning of the characters between the brackets of a task. Characters that
+/// are not mapped are done for `x` and `X` and open otherwise.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusMap(HashMap<char, TaskState>);
+
+impl StatusMap {
+    pub fn new(states: HashMap<char, TaskState>) -> Self {
+        StatusMap(states)
+    }
+
+    /// State of a task item with the status character `ch`
+    pub fn state(&self, ch: Option<char>) -> TaskState {
+        match ch.and_then(|ch| self.0.get(&ch)) {
+            Some(state) => *state,
+            None => match ch {
+                Some('x') | Some('X') => TaskState::Done,
+                _ => TaskState::Open,
+            },
+        }
+    }
+
+    /// State of a task with `status`
+    pub fn status_state(&self, status: &Status) -> TaskState {
+        self.state(status.task_char())
+    }
+}
+
+impl From<&HashMap<char, TaskState>> for StatusMap {
+    fn from(states: &HashMap<char, TaskState>) -> Self {
+        StatusMap(states.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_status_map() {
+        let states = StatusMap::default();
+        assert_eq!(states.state(Some('x')), TaskState::Done);
+        assert_eq!(states.state(Some('X')), TaskState::Done);
+        assert_eq!(states.state(Some('-')), TaskState::Open);
+        assert_eq!(states.state(Some(' ')), TaskState::Open);
+        assert_eq!(states.state(None), TaskState::Open);
+
+        let states = StatusMap::new(HashMap::from([
+            ('-', TaskState::Cancelled),
+            ('/', TaskState::InProgress),
+            ('X', TaskState::Cancelled),
+        ]));
+        assert_eq!(states.state(Some('-')), TaskState::Cancelled);
+        assert_eq!(
+            states.status_state(&Status::Todo('/')),
+            TaskState::InProgress
+        );
+        assert_eq!(states.state(Some('X')), TaskState::Cancelled);
+        assert_eq!(states.status_state(&Status::Done('x')), TaskState::Done);
+        assert_eq!(states.status_state(&Status::Empty), TaskState::Open);
+        assert!(states.state(Some('-')).is_finished());
+        assert!(!states.state(Some('/')).is_finished());
+
+        let states: HashMap<char, TaskState> =
+            serde_json::from_str(r#"{"-": "cancelled", ">": "deferred", "/": "in-progress"}"#)
+                .unwrap();
+        assert_eq!(states[&'/'], TaskState::InProgress);
+        assert_eq!("In-Progress".parse(), Ok(TaskState::InProgress));
+        assert!("finished".parse::<TaskState>().is_err());
+    }
+}
@@ -0,0 +1,64 @@
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)#([\w/-]+)").expect("tag regex is not parsable"));
+static CONTEXT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:^|\s)@([\w/-]+)").expect("context regex is not parsable"));
+/// `#` of a tag escaped by the markdown renderer
+static ESCAPED_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)(^|\s)\\#([\w/-])").expect("escaped tag regex is not parsable")
+});
+
+/// Names matched by `re` in order of appearance, without duplicates
+fn find_names(re: &Regex, text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for caps in re.captures_iter(text) {
+        let name = &caps[1];
+        // `#1` is more likely an issue number than a tag
+        if name.chars().all(|c| c.is_ascii_digit()) || names.iter().any(|n| n == name) {
+            continue;
+        }
+        names.push(name.to_string());
+    }
+    names
+}
+
+/// `#tag` tokens in the text of a task, without the `#`
+pub fn parse_tags(text: &str) -> Vec<String> {
+    find_names(&TAG_RE, text)
+}
+
+/// `@context` tokens in the text of a task, without the `@`
+pub fn parse_contexts(text: &str) -> Vec<String> {
+    find_names(&CONTEXT_RE, text)
+}
+
+/// Rendered `markdown` with the tags written back as `#tag`, a `#` is never
+/// a heading when it is followed by a word
+pub fn unescape_tags(markdown: &str) -> Cow<'_, str> {
+    ESCAPED_TAG_RE.replace_all(markdown, "$1#$2")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let text = "#work fix issue #12 @office with me@example.com #work/backend #work";
+        assert_eq!(parse_tags(text), vec!["work", "work/backend"]);
+        assert_eq!(parse_contexts(text), vec!["office"]);
+        assert_eq!(parse_tags("call mom @phone"), Vec::<String>::new());
+        assert_eq!(
+            parse_contexts("call mom @phone, @home"),
+            vec!["phone", "home"]
+        );
+
+        assert_eq!(
+            unescape_tags("- [ ] \\#work \\#1 a\\#b \\# c\n\\#work/backend"),
+            "- [ ] #work #1 a\\#b \\# c\n#work/backend"
+        );
+    }
+}
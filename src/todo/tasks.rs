@@ -1,9 +1,15 @@
-use std::borrow::Borrow;
+use std::fmt;
 
+use super::dates::TaskDates;
+use super::id::parse_id;
+use super::priority::Priority;
+use super::tags::{parse_contexts, parse_tags};
+use chrono::NaiveDate;
 use comrak::nodes::AstNode;
 use comrak::nodes::NodeValue;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TaskGroup {
     pub name: String,
     pub tasks: Vec<Task>,
@@ -11,31 +17,113 @@ pub struct TaskGroup {
 }
 
 // This does not support subtasks, need to figure out best path forward
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     pub status: Status,
     pub text: String,
     pub subtasks: Option<Vec<Task>>,
+    /// dates parsed from `text`
+    #[serde(default)]
+    pub dates: TaskDates,
+    /// `#tag` tokens in `text`, without the `#`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `@context` tokens in `text`, without the `@`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<String>,
+    /// priority marker in `text`
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// block reference at the end of `text` identifying the task, without
+    /// the `^`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 1 based line of the task in the file it was parsed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "state", content = "char", rename_all = "lowercase")]
 pub enum Status {
     Done(char),
     Todo(char),
+    #[default]
     Empty,
 }
 
+impl Status {
+    /// Character between the brackets of the task, `None` when empty
+    pub fn task_char(&self) -> Option<char> {
+        match self {
+            Status::Done(ch) | Status::Todo(ch) => Some(*ch),
+            Status::Empty => None,
+        }
+    }
+}
+
+impl From<Option<char>> for Status {
+    fn from(ch: Option<char>) -> Self {
+        match ch {
+            Some(c) if c == 'x' || c == 'X' => Status::Done(c),
+            Some(' ') | None => Status::Empty,
+            Some(c) => Status::Todo(c),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub enum TaskError {
-    ParsingError(&'static str),
+    ParsingError { line: usize, reason: &'static str },
+}
+
+impl TaskError {
+    fn parsing<'a>(node: &'a AstNode<'a>, reason: &'static str) -> Self {
+        TaskError::ParsingError {
+            line: node.data.borrow().sourcepos.start.line,
+            reason,
+        }
+    }
 }
 
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::ParsingError { line, reason } => write!(f, "{}: {}", line, reason),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
 impl Task {
+    /// days since the task was first seen, if that is recorded
+    pub fn age(&self, today: &NaiveDate) -> Option<i64> {
+        self.dates
+            .created
+            .map(|created| (*today - created).num_days())
+    }
+
+    /// whether the task has `tag`, ignoring case and a leading `#`
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.strip_prefix('#').unwrap_or(tag);
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// whether the task has `context`, ignoring case and a leading `@`
+    pub fn has_context(&self, context: &str) -> bool {
+        let context = context.strip_prefix('@').unwrap_or(context);
+        self.contexts
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(context))
+    }
+
     fn extract_text<'a>(node: &'a AstNode<'a>) -> Result<String, TaskError> {
         let data_ref = node.data.borrow();
         if let NodeValue::Text(contents) = &data_ref.value {
             Ok(contents.to_string())
         } else {
-            Err(TaskError::ParsingError("Could not get text from element"))
+            Err(TaskError::parsing(node, "Could not get text from element"))
         }
     }
 
@@ -45,7 +133,7 @@ impl Task {
         if let NodeValue::Paragraph = data_ref.value {
             for child in node.children() {
                 let child_data_ref = child.data.borrow();
-                let t = match &child_data_ref.borrow().value {
+                let t = match &child_data_ref.value {
                     NodeValue::Text(contents) => contents.clone(),
                     NodeValue::Emph if child.first_child().is_some() => {
                         format!("*{}*", Self::extract_text(child.first_child().unwrap())?)
@@ -62,18 +150,14 @@ impl Task {
             }
             Ok(text)
         } else {
-            Err(TaskError::ParsingError("First child is not Paragraph"))
+            Err(TaskError::parsing(node, "First child is not Paragraph"))
         }
     }
 }
 
-impl ToString for Task {
-    fn to_string(&self) -> String {
-        let ch = match self.status {
-            Status::Done(ch) => ch,
-            Status::Todo(ch) => ch,
-            Status::Empty => ' ',
-        };
+impl fmt::Display for Task {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ch = self.status.task_char().unwrap_or(' ');
 
         let subtasks = if let Some(subtasks) = &self.subtasks {
             let text = subtasks
@@ -81,12 +165,12 @@ impl ToString for Task {
                 .map(|task| task.to_string())
                 .collect::<Vec<_>>()
                 .join("\n");
-            format!("\n{}", text).trim_end().replace("\n", "\n  ")
+            format!("\n{}", text).trim_end().replace('\n', "\n  ")
         } else {
             "".into()
         };
 
-        format!("- [{}] {}{}\n", ch, self.text.trim(), subtasks)
+        writeln!(f, "- [{}] {}{}", ch, self.text.trim(), subtasks)
     }
 }
 
@@ -98,26 +182,15 @@ impl<'a> TryFrom<&'a AstNode<'a>> for Task {
         if let NodeValue::TaskItem(ch) = data_ref.value {
             let text = Self::extract_text_from_task(
                 node.first_child()
-                    .ok_or(TaskError::ParsingError("No childern of node found"))?,
+                    .ok_or_else(|| TaskError::parsing(node, "No childern of node found"))?,
             )?;
-            let status = match ch {
-                Some(c) if c == 'x' || c == 'X' => Status::Done(c),
-                Some(c) => Status::Todo(c),
-                _ => Status::Empty,
-            };
+            let status = Status::from(ch);
             let subtasks = node
                 .children()
-                .filter_map(|child| {
-                    if let NodeValue::List(_) = child.data.borrow().value {
-                        Some(child)
-                    } else {
-                        None
-                    }
-                })
+                .filter(|child| matches!(child.data.borrow().value, NodeValue::List(_)))
                 .map(|child| {
                     child
                         .children()
-                        .into_iter()
                         .filter_map(|item_node| Task::try_from(item_node).ok())
                         .collect()
                 })
@@ -125,11 +198,18 @@ impl<'a> TryFrom<&'a AstNode<'a>> for Task {
 
             Ok(Self {
                 status,
+                dates: TaskDates::parse(&text),
+                tags: parse_tags(&text),
+                contexts: parse_contexts(&text),
+                priority: Priority::parse(&text),
+                id: parse_id(&text),
+                line: Some(data_ref.sourcepos.start.line).filter(|line| *line > 0),
                 text,
                 subtasks,
             })
         } else {
-            Err(TaskError::ParsingError(
+            Err(TaskError::parsing(
+                node,
                 "Node being parsed is not a TaskItem",
             ))
         }
@@ -145,15 +225,10 @@ impl TaskGroup {
         }
     }
 }
-impl ToString for TaskGroup {
-    fn to_string(&self) -> String {
-        let mut output = String::new();
-        output.push_str(format!("{} {}\n", "#".repeat(self.level.into()), self.name).as_str());
-        self.tasks
-            .iter()
-            .for_each(|task| output.push_str(task.to_string().as_str()));
-
-        output
+impl fmt::Display for TaskGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} {}", "#".repeat(self.level.into()), self.name)?;
+        self.tasks.iter().try_for_each(|task| write!(f, "{}", task))
     }
 }
 
@@ -166,36 +241,76 @@ impl<'a> TryFrom<&'a AstNode<'a>> for TaskGroup {
             let level = heading.level;
             let first_child = node
                 .first_child()
-                .ok_or(TaskError::ParsingError("Node has no children"))?;
+                .ok_or_else(|| TaskError::parsing(node, "Node has no children"))?;
 
             let data_ref = first_child.data.borrow();
             let name = if let NodeValue::Text(value) = &data_ref.value {
                 Ok(value.to_string())
             } else {
-                Err(TaskError::ParsingError(
+                Err(TaskError::parsing(
+                    node,
                     "Could not get title from heading node",
                 ))
             }?;
 
             let next_sib = node
                 .next_sibling()
-                .ok_or(TaskError::ParsingError("Empty section at end of file"))?;
+                .ok_or_else(|| TaskError::parsing(node, "Empty section at end of file"))?;
 
             if let NodeValue::List(_list_meta) = next_sib.data.borrow().value {
                 let tasks = next_sib
                     .children()
-                    .into_iter()
                     .filter_map(|item_node| Task::try_from(item_node).ok())
                     .collect();
 
                 Ok(TaskGroup { name, tasks, level })
             } else {
-                Err(TaskError::ParsingError(
+                Err(TaskError::parsing(
+                    node,
                     "Next sibling of node is not a list",
                 ))
             }
         } else {
-            Err(TaskError::ParsingError("Node is not a section heading"))
+            Err(TaskError::parsing(node, "Node is not a section heading"))
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::file::{parse_todo_file, render_doc};
+    use comrak::Arena;
+
+    #[test]
+    fn test_task_dates_round_trip() {
+        let contents = "\
+- [ ] report 🛫 2024-01-01 ⏳ 2024-01-02 📅 2024-01-05 ➕ 2023-12-30
+- [x] deploy start:2024-01-01 scheduled:2024-01-02 due:2024-01-05 done:2024-01-04
+";
+        let arena = Arena::new();
+        let root = parse_todo_file(contents, &arena);
+        assert_eq!(render_doc(root).unwrap(), contents);
+
+        let tasks: Vec<Task> = root
+            .first_child()
+            .unwrap()
+            .children()
+            .map(|item| Task::try_from(item).unwrap())
+            .collect();
+        let date = |d| NaiveDate::from_ymd_opt(2024, 1, d);
+        for task in &tasks {
+            assert_eq!(task.dates.start, date(1));
+            assert_eq!(task.dates.scheduled, date(2));
+            assert_eq!(task.dates.due, date(5));
+        }
+        assert_eq!(
+            tasks[0].dates.created,
+            NaiveDate::from_ymd_opt(2023, 12, 30)
+        );
+        assert_eq!(tasks[1].dates.completed, date(4));
+
+        let rendered: String = tasks.iter().map(|task| task.to_string()).collect();
+        assert_eq!(rendered, contents);
+    }
+}